@@ -0,0 +1,95 @@
+use ndarray::Array2;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+/// Blue for -1, white for 0 and red for +1; grey marks undefined correlations
+fn correlation_color(value: f64) -> RGBColor {
+    if value.is_nan() {
+        return RGBColor(200, 200, 200);
+    }
+    let t = value.clamp(-1.0, 1.0);
+    let fade = (255.0 * (1.0 - t.abs())) as u8;
+    if t >= 0.0 {
+        RGBColor(255, fade, fade)
+    } else {
+        RGBColor(fade, fade, 255)
+    }
+}
+
+/// Draw a correlation matrix, such as the one from `correlation_matrix`, as
+/// a heatmap with the coefficient written in every cell.
+///
+/// # Arguments
+///
+/// * `matrix` - Square matrix of correlations in [-1, 1], at least 2x2
+/// * `labels` - Column names, one per row of the matrix
+/// * `title` - Chart caption
+/// * `file_name` - Output PNG path
+pub fn correlation_heatmap(
+    matrix: &Array2<f64>,
+    labels: &[&str],
+    title: &str,
+    file_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let n = matrix.nrows();
+    if n < 2 || matrix.ncols() != n {
+        return Err("La matriz de correlacion debe ser cuadrada y de al menos 2x2".into());
+    }
+    if labels.len() != n {
+        return Err("Tiene que haber una etiqueta por columna".into());
+    }
+
+    let root = BitMapBackend::new(file_name, (800, 700)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 30))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(100)
+        .build_cartesian_2d((0..n - 1).into_segmented(), (0..n - 1).into_segmented())?;
+
+    // Row 0 is drawn at the top, like the printed matrix
+    let label = |value: &SegmentValue<usize>, flip: bool| match value {
+        SegmentValue::CenterOf(i) if *i < n => {
+            let index = if flip { n - 1 - *i } else { *i };
+            labels[index].to_string()
+        }
+        _ => String::new(),
+    };
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_labels(n)
+        .y_labels(n)
+        .x_label_formatter(&|v| label(v, false))
+        .y_label_formatter(&|v| label(v, true))
+        .label_style(("sans-serif", 16))
+        .draw()?;
+
+    chart.draw_series((0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| {
+        let row = n - 1 - i;
+        Rectangle::new(
+            [
+                (SegmentValue::Exact(j), SegmentValue::Exact(row)),
+                (SegmentValue::Exact(j + 1), SegmentValue::Exact(row + 1)),
+            ],
+            correlation_color(matrix[[i, j]]).filled(),
+        )
+    }))?;
+
+    chart.draw_series((0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| {
+        Text::new(
+            format!("{:.2}", matrix[[i, j]]),
+            (SegmentValue::CenterOf(j), SegmentValue::CenterOf(n - 1 - i)),
+            ("sans-serif", 18)
+                .into_font()
+                .color(&BLACK)
+                .pos(Pos::new(HPos::Center, VPos::Center)),
+        )
+    }))?;
+
+    root.present()?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod graphing_example;
 pub mod box_plot;
-pub mod horizontal_graph;
\ No newline at end of file
+pub mod horizontal_graph;
+pub mod heatmap;
\ No newline at end of file
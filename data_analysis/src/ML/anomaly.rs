@@ -0,0 +1,660 @@
+// src/ml/anomaly.rs
+use std::collections::VecDeque;
+
+use ndarray::Array1;
+use statrs::statistics::{Data, OrderStatistics};
+
+use crate::ML::data_imputation::{impute_nan, ImputationStrategy};
+use crate::ML::forecaster::Forecaster;
+
+/// Scale factor turning a median absolute deviation into a normal standard deviation
+const MAD_SCALE: f64 = 1.4826;
+
+/// A flagged observation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub index: usize,  // Position in the batch, or in the stream since the last reset
+    pub value: f64,
+    pub expected: f64,  // Centre the value was compared against
+    pub score: f64,  // Signed deviation in the detector's units (sigmas or IQRs)
+}
+
+/// Common interface of the anomaly detectors.
+///
+/// `update` consumes a stream one reading at a time; `detect` runs a whole
+/// series from a fresh state. NaN readings are skipped but still advance the index.
+pub trait AnomalyDetector {
+    /// Feed the next reading, returning it as an `Anomaly` when flagged
+    fn update(&mut self, value: f64) -> Result<Option<Anomaly>, String>;
+
+    /// Forget every reading seen so far
+    fn reset(&mut self);
+
+    /// Run the detector over a whole series
+    fn detect(&mut self, data: &[f64]) -> Result<Vec<Anomaly>, String> {
+        self.reset();
+        let mut anomalies = Vec::new();
+        for &value in data {
+            if let Some(anomaly) = self.update(value)? {
+                anomalies.push(anomaly);
+            }
+        }
+        Ok(anomalies)
+    }
+}
+
+fn median_of(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = values.len();
+    if n % 2 == 0 {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    } else {
+        values[n / 2]
+    }
+}
+
+/// Median and MAD-based standard deviation of a set of values
+fn median_and_mad(values: &[f64]) -> (f64, f64) {
+    let mut sorted = values.to_vec();
+    let median = median_of(&mut sorted);
+    let mut deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+    (median, MAD_SCALE * median_of(&mut deviations))
+}
+
+/// Signed number of scales between value and centre; infinite when the scale is zero
+fn standardized(value: f64, centre: f64, scale: f64) -> f64 {
+    let deviation = value - centre;
+    if scale > 0.0 {
+        deviation / scale
+    } else if deviation == 0.0 {
+        0.0
+    } else {
+        deviation.signum() * f64::INFINITY
+    }
+}
+
+/// Flags readings more than `threshold` standard deviations from the mean of
+/// the previous `window` readings.
+pub struct RollingZScore {
+    pub window: usize,
+    pub threshold: f64,
+    recent: VecDeque<f64>,
+    sum: f64,
+    sum_squares: f64,
+    index: usize,
+}
+
+impl RollingZScore {
+    /// Create a rolling z-score detector
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Number of previous readings forming the baseline
+    /// * `threshold` - Number of standard deviations that counts as anomalous, e.g. 3.0
+    pub fn new(window: usize, threshold: f64) -> Self {
+        RollingZScore {
+            window,
+            threshold,
+            recent: VecDeque::with_capacity(window + 1),
+            sum: 0.0,
+            sum_squares: 0.0,
+            index: 0,
+        }
+    }
+}
+
+impl AnomalyDetector for RollingZScore {
+    fn update(&mut self, value: f64) -> Result<Option<Anomaly>, String> {
+        if self.window < 2 {
+            return Err("Rolling z-score needs a window of at least two readings".to_string());
+        }
+        let index = self.index;
+        self.index += 1;
+        if value.is_nan() {
+            return Ok(None);
+        }
+
+        let mut result = None;
+        if self.recent.len() == self.window {
+            let n = self.window as f64;
+            let mean = self.sum / n;
+            let variance = ((self.sum_squares - n * mean * mean) / (n - 1.0)).max(0.0);
+            let score = standardized(value, mean, variance.sqrt());
+            if score.abs() > self.threshold {
+                result = Some(Anomaly { index, value, expected: mean, score });
+            }
+
+            let oldest = self.recent.pop_front().unwrap_or(0.0);
+            self.sum -= oldest;
+            self.sum_squares -= oldest * oldest;
+        }
+
+        self.recent.push_back(value);
+        self.sum += value;
+        self.sum_squares += value * value;
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+        self.sum = 0.0;
+        self.sum_squares = 0.0;
+        self.index = 0;
+    }
+}
+
+/// Hampel identifier: flags readings more than `threshold` scaled MADs from
+/// the median of their neighbourhood.
+///
+/// In batch the neighbourhood is centred, `half_window` readings on each side.
+/// On a stream only the past is known, so the newest reading is compared with
+/// the trailing `2 * half_window + 1` readings that end with it.
+pub struct Hampel {
+    pub half_window: usize,
+    pub threshold: f64,
+    recent: VecDeque<f64>,
+    index: usize,
+}
+
+impl Hampel {
+    /// Create a Hampel identifier, `threshold` is usually 3.0
+    pub fn new(half_window: usize, threshold: f64) -> Self {
+        Hampel {
+            half_window,
+            threshold,
+            recent: VecDeque::with_capacity(2 * half_window + 1),
+            index: 0,
+        }
+    }
+
+    fn check(&self, index: usize, value: f64, neighbourhood: &[f64]) -> Option<Anomaly> {
+        let (median, sigma) = median_and_mad(neighbourhood);
+        let score = standardized(value, median, sigma);
+        if score.abs() > self.threshold {
+            Some(Anomaly { index, value, expected: median, score })
+        } else {
+            None
+        }
+    }
+
+    /// Hampel filter: the series with every flagged reading replaced by its
+    /// neighbourhood median
+    pub fn filter(&mut self, data: &[f64]) -> Result<Vec<f64>, String> {
+        let mut cleaned = data.to_vec();
+        for anomaly in self.detect(data)? {
+            cleaned[anomaly.index] = anomaly.expected;
+        }
+        Ok(cleaned)
+    }
+}
+
+impl AnomalyDetector for Hampel {
+    fn update(&mut self, value: f64) -> Result<Option<Anomaly>, String> {
+        if self.half_window == 0 {
+            return Err("Hampel window must be at least one reading on each side".to_string());
+        }
+        let index = self.index;
+        self.index += 1;
+        if value.is_nan() {
+            return Ok(None);
+        }
+
+        if self.recent.len() == 2 * self.half_window + 1 {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(value);
+        if self.recent.len() < 2 * self.half_window + 1 {
+            return Ok(None);
+        }
+
+        let neighbourhood: Vec<f64> = self.recent.iter().copied().collect();
+        Ok(self.check(index, value, &neighbourhood))
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+        self.index = 0;
+    }
+
+    /// Centred windows, truncated at both ends of the series
+    fn detect(&mut self, data: &[f64]) -> Result<Vec<Anomaly>, String> {
+        if self.half_window == 0 {
+            return Err("Hampel window must be at least one reading on each side".to_string());
+        }
+        self.reset();
+
+        let n = data.len();
+        let mut anomalies = Vec::new();
+        for (i, &value) in data.iter().enumerate() {
+            if value.is_nan() {
+                continue;
+            }
+            let start = i.saturating_sub(self.half_window);
+            let end = (i + self.half_window + 1).min(n);
+            let neighbourhood: Vec<f64> = data[start..end].iter().copied().filter(|v| !v.is_nan()).collect();
+            if let Some(anomaly) = self.check(i, value, &neighbourhood) {
+                anomalies.push(anomaly);
+            }
+        }
+        Ok(anomalies)
+    }
+}
+
+/// Tukey fences `[Q1 - k IQR, Q3 + k IQR]`, the rule `BoxplotData` uses for its outliers
+fn tukey_fences(values: &[f64], multiplier: f64) -> (f64, f64, f64) {
+    let mut data = Data::new(values.to_vec());
+    let q1 = data.lower_quartile();
+    let q3 = data.upper_quartile();
+    let iqr = q3 - q1;
+    (q1 - multiplier * iqr, q3 + multiplier * iqr, iqr)
+}
+
+fn fence_anomaly(index: usize, value: f64, fences: (f64, f64, f64)) -> Option<Anomaly> {
+    let (lower, upper, iqr) = fences;
+    if value < lower {
+        Some(Anomaly { index, value, expected: lower, score: standardized(value, lower, iqr) })
+    } else if value > upper {
+        Some(Anomaly { index, value, expected: upper, score: standardized(value, upper, iqr) })
+    } else {
+        None
+    }
+}
+
+/// Flag every value outside the Tukey fences of the whole series.
+///
+/// The score is the distance beyond the crossed fence in IQRs and `expected`
+/// holds that fence. With `multiplier` 1.5 this flags exactly the outliers of
+/// a `BoxplotData` built from the same values.
+pub fn iqr_outliers(data: &[f64], multiplier: f64) -> Vec<Anomaly> {
+    let values: Vec<f64> = data.iter().copied().filter(|v| !v.is_nan()).collect();
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let fences = tukey_fences(&values, multiplier);
+    data.iter()
+        .enumerate()
+        .filter(|(_, v)| !v.is_nan())
+        .filter_map(|(i, &v)| fence_anomaly(i, v, fences))
+        .collect()
+}
+
+/// Flags readings outside the Tukey fences of the previous `window` readings
+pub struct RollingIqr {
+    pub window: usize,
+    pub multiplier: f64,  // 1.5 for the usual outliers, 3.0 for far outliers
+    recent: VecDeque<f64>,
+    index: usize,
+}
+
+impl RollingIqr {
+    /// Create a rolling IQR detector
+    pub fn new(window: usize, multiplier: f64) -> Self {
+        RollingIqr {
+            window,
+            multiplier,
+            recent: VecDeque::with_capacity(window + 1),
+            index: 0,
+        }
+    }
+}
+
+impl AnomalyDetector for RollingIqr {
+    fn update(&mut self, value: f64) -> Result<Option<Anomaly>, String> {
+        if self.window < 4 {
+            return Err("Rolling IQR needs a window of at least four readings".to_string());
+        }
+        let index = self.index;
+        self.index += 1;
+        if value.is_nan() {
+            return Ok(None);
+        }
+
+        let mut result = None;
+        if self.recent.len() == self.window {
+            let baseline: Vec<f64> = self.recent.iter().copied().collect();
+            result = fence_anomaly(index, value, tukey_fences(&baseline, self.multiplier));
+            self.recent.pop_front();
+        }
+        self.recent.push_back(value);
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+        self.index = 0;
+    }
+}
+
+/// Flags readings whose forecast error is more than `threshold` standard
+/// deviations, using any `Forecaster` as the model of normal behaviour.
+///
+/// On a stream the first `warmup` readings train the model, which then
+/// forecasts `refit_every` steps ahead and is refitted on the recent history
+/// once they are used up. Flagged readings are replaced by their forecast in
+/// that history so a spike does not distort the next fit. The error scale is
+/// the model's forecast variance when it has one and the MAD of its in-sample
+/// residuals otherwise. A failed refit does not interrupt the stream, see
+/// `refit_error`.
+pub struct ResidualDetector<F: Forecaster> {
+    pub warmup: usize,
+    pub refit_every: usize,
+    pub threshold: f64,
+    model: F,
+    history: Vec<f64>,
+    forecast: Option<(Array1<f64>, Array1<f64>)>,  // Forecasts and their standard deviations
+    since_fit: usize,
+    index: usize,
+    refit_error: Option<String>,  // Why the last refit failed, cleared by the next successful one
+}
+
+impl<F: Forecaster> ResidualDetector<F> {
+    /// Create a residual detector that refits every `warmup / 4` readings (at least one)
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Forecaster describing normal behaviour
+    /// * `warmup` - Readings used for each fit
+    /// * `threshold` - Number of standard deviations that counts as anomalous
+    pub fn new(model: F, warmup: usize, threshold: f64) -> Self {
+        ResidualDetector {
+            warmup,
+            refit_every: (warmup / 4).max(1),
+            threshold,
+            model,
+            history: Vec::with_capacity(warmup),
+            forecast: None,
+            since_fit: 0,
+            index: 0,
+            refit_error: None,
+        }
+    }
+
+    /// The wrapped model, as of its last fit
+    pub fn model(&self) -> &F {
+        &self.model
+    }
+
+    /// Why the last refit failed, `None` once a refit succeeds.
+    ///
+    /// After a failure the detector warms up again and retries the fit on
+    /// every reading until it succeeds, flagging nothing in between.
+    pub fn refit_error(&self) -> Option<&str> {
+        self.refit_error.as_deref()
+    }
+
+    fn residual_scale(&self) -> Result<f64, String> {
+        let residuals: Vec<f64> = self.model.residuals()?.iter().copied().filter(|r| !r.is_nan()).collect();
+        if residuals.is_empty() {
+            return Err("Model has no residuals to estimate the error scale".to_string());
+        }
+        let mut deviations: Vec<f64> = residuals.iter().map(|r| r.abs()).collect();
+        Ok(MAD_SCALE * median_of(&mut deviations))
+    }
+
+    fn refit(&mut self) -> Result<(), String> {
+        let start = self.history.len().saturating_sub(self.warmup);
+        self.history.drain(..start);
+        self.model.fit(&Array1::from(self.history.clone()))?;
+
+        let means = self.model.predict(self.refit_every)?;
+        let deviations = match self.model.forecast_variance(self.refit_every) {
+            Ok(variance) => variance.mapv(f64::sqrt),
+            Err(_) => Array1::from_elem(self.refit_every, self.residual_scale()?),
+        };
+        self.forecast = Some((means, deviations));
+        self.since_fit = 0;
+        Ok(())
+    }
+}
+
+impl<F: Forecaster> AnomalyDetector for ResidualDetector<F> {
+    fn update(&mut self, value: f64) -> Result<Option<Anomaly>, String> {
+        if self.refit_every == 0 {
+            return Err("Refit interval must be positive".to_string());
+        }
+        let index = self.index;
+        self.index += 1;
+        if value.is_nan() {
+            return Ok(None);
+        }
+
+        let mut result = None;
+        let mut kept = value;
+        if let Some((means, deviations)) = &self.forecast {
+            let expected = means[self.since_fit];
+            let score = standardized(value, expected, deviations[self.since_fit]);
+            if score.abs() > self.threshold {
+                result = Some(Anomaly { index, value, expected, score });
+                kept = expected;
+            }
+            self.since_fit += 1;
+        }
+        self.history.push(kept);
+
+        let due = match self.forecast {
+            Some(_) => self.since_fit == self.refit_every,
+            None => self.history.len() >= self.warmup,
+        };
+        if due {
+            match self.refit() {
+                Ok(()) => self.refit_error = None,
+                Err(e) => {
+                    // Back to warming up, so the next reading retries the fit
+                    self.forecast = None;
+                    self.since_fit = 0;
+                    self.refit_error = Some(e);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.forecast = None;
+        self.since_fit = 0;
+        self.index = 0;
+        self.refit_error = None;
+    }
+
+    /// Fit once on the whole series and flag large in-sample residuals,
+    /// scaled by their MAD so the anomalies themselves do not inflate the scale.
+    /// NaN gaps are filled linearly for the fit and never flagged.
+    fn detect(&mut self, data: &[f64]) -> Result<Vec<Anomaly>, String> {
+        self.reset();
+        let filled = impute_nan(data, ImputationStrategy::Linear)?;
+        self.model.fit(&Array1::from(filled.values))?;
+        let fitted = self.model.fitted_values()?;
+        let residuals = self.model.residuals()?;
+        let scale = self.residual_scale()?;
+        let offset = data.len() - residuals.len();
+
+        Ok(residuals
+            .iter()
+            .enumerate()
+            .filter(|&(i, r)| !r.is_nan() && !data[offset + i].is_nan())
+            .filter_map(|(i, &r)| {
+                let score = standardized(r, 0.0, scale);
+                if score.abs() > self.threshold {
+                    Some(Anomaly { index: offset + i, value: data[offset + i], expected: fitted[i], score })
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::arima::ARIMA;
+    use crate::ML::test_utils::uniform_noise;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Noisy sine with spikes at 60 and 130
+    fn spiky_series() -> Vec<f64> {
+        let e = uniform_noise(200, 5);
+        let mut data: Vec<f64> = (0..200).map(|t| 20.0 + 2.0 * (t as f64 / 10.0).sin() + 0.2 * e[t]).collect();
+        data[60] += 8.0;
+        data[130] -= 8.0;
+        data
+    }
+
+    fn indices(anomalies: &[Anomaly]) -> Vec<usize> {
+        anomalies.iter().map(|a| a.index).collect()
+    }
+
+    #[test]
+    fn test_rolling_z_score() {
+        let mut detector = RollingZScore::new(30, 4.0);
+        let anomalies = detector.detect(&spiky_series()).unwrap();
+        assert_eq!(indices(&anomalies), vec![60, 130]);
+        assert!(anomalies[0].score > 4.0 && anomalies[1].score < -4.0);
+    }
+
+    #[test]
+    fn test_stream_matches_batch() {
+        let data = spiky_series();
+        let mut batch = RollingIqr::new(20, 3.0);
+        let expected = batch.detect(&data).unwrap();
+
+        let mut stream = RollingIqr::new(20, 3.0);
+        let mut flagged = Vec::new();
+        for &value in &data {
+            if let Some(anomaly) = stream.update(value).unwrap() {
+                flagged.push(anomaly);
+            }
+        }
+        assert_eq!(flagged, expected);
+        assert_eq!(indices(&flagged), vec![60, 130]);
+    }
+
+    #[test]
+    fn test_hampel_filter_removes_spikes() {
+        let data = spiky_series();
+        let mut hampel = Hampel::new(5, 3.0);
+        assert_eq!(indices(&hampel.detect(&data).unwrap()), vec![60, 130]);
+
+        let cleaned = hampel.filter(&data).unwrap();
+        assert!((cleaned[60] - (20.0 + 2.0 * 6f64.sin())).abs() < 0.5);
+        assert_eq!(cleaned[59], data[59]);
+
+        // The trailing streaming window sees the spike as its newest reading
+        let mut stream = Hampel::new(5, 3.0);
+        let flagged: Vec<usize> = data
+            .iter()
+            .filter_map(|&v| stream.update(v).unwrap())
+            .map(|a| a.index)
+            .collect();
+        assert!(flagged.contains(&60) && flagged.contains(&130));
+    }
+
+    #[test]
+    fn test_iqr_outliers_match_boxplot_rule() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 30.0, f64::NAN];
+        let anomalies = iqr_outliers(&data, 1.5);
+        assert_eq!(indices(&anomalies), vec![8]);
+
+        let mut values = Data::new(data[..9].to_vec());
+        let upper = values.upper_quartile() + 1.5 * (values.upper_quartile() - values.lower_quartile());
+        assert!((anomalies[0].expected - upper).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_residual_detector() {
+        let data = spiky_series();
+        let mut detector = ResidualDetector::new(ARIMA::new(2, 0, 0), 50, 5.0);
+        detector.refit_every = 1;
+
+        let anomalies = detector.detect(&data).unwrap();
+        assert!(indices(&anomalies).contains(&60) && indices(&anomalies).contains(&130));
+
+        // A dropped reading is bridged for the fit and not reported
+        let mut gappy = data.clone();
+        gappy[100] = f64::NAN;
+        assert_eq!(indices(&detector.detect(&gappy).unwrap()), indices(&anomalies));
+
+        detector.reset();
+        let flagged: Vec<usize> = data
+            .iter()
+            .filter_map(|&v| detector.update(v).unwrap())
+            .map(|a| a.index)
+            .collect();
+        assert_eq!(flagged, vec![60, 130]);
+    }
+
+    /// Mean model whose fit fails while `fail` is set
+    struct Switchable {
+        fail: Rc<Cell<bool>>,
+        mean: f64,
+        len: usize,
+    }
+
+    impl Forecaster for Switchable {
+        fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+            if self.fail.get() {
+                return Err("Fit failed".to_string());
+            }
+            self.mean = data.mean().unwrap();
+            self.len = data.len();
+            Ok(())
+        }
+
+        fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+            Ok(Array1::from_elem(horizon, self.mean))
+        }
+
+        fn fitted_values(&self) -> Result<Array1<f64>, String> {
+            Ok(Array1::from_elem(self.len, self.mean))
+        }
+
+        fn residuals(&self) -> Result<Array1<f64>, String> {
+            Ok(Array1::zeros(self.len))
+        }
+
+        fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+            Ok(Array1::ones(horizon))
+        }
+    }
+
+    #[test]
+    fn test_residual_detector_recovers_from_failed_refit() {
+        let fail = Rc::new(Cell::new(true));
+        let model = Switchable { fail: Rc::clone(&fail), mean: 0.0, len: 0 };
+        let mut detector = ResidualDetector::new(model, 4, 3.0);
+        detector.refit_every = 2;
+
+        // The first fit fails, the next reading retries it
+        for _ in 0..4 {
+            assert_eq!(detector.update(1.0).unwrap(), None);
+        }
+        assert_eq!(detector.refit_error(), Some("Fit failed"));
+        fail.set(false);
+        assert_eq!(detector.update(1.0).unwrap(), None);
+        assert_eq!(detector.refit_error(), None);
+
+        // A failed refit drops the used-up forecast instead of reading past
+        // it, and the spike that triggered it is still reported
+        assert_eq!(detector.update(1.0).unwrap(), None);
+        fail.set(true);
+        let anomaly = detector.update(100.0).unwrap().unwrap();
+        assert_eq!((anomaly.index, anomaly.expected), (6, 1.0));
+        assert!(detector.refit_error().is_some());
+        fail.set(false);
+        assert_eq!(detector.update(1.0).unwrap(), None);
+        let anomaly = detector.update(-100.0).unwrap().unwrap();
+        assert_eq!((anomaly.index, anomaly.expected), (8, 1.0));
+    }
+
+    #[test]
+    fn test_nan_readings_are_skipped() {
+        let mut detector = RollingZScore::new(3, 3.0);
+        for &value in &[1.0, 1.1, f64::NAN, 0.9] {
+            assert_eq!(detector.update(value).unwrap(), None);
+        }
+        let anomaly = detector.update(10.0).unwrap().unwrap();
+        assert_eq!(anomaly.index, 4);
+    }
+}
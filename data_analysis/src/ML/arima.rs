@@ -1,5 +1,12 @@
 use ndarray::{Array1, Array2, s};
-use ndarray_linalg::Solve;
+use ndarray_linalg::{Inverse, Solve};
+use statrs::distribution::{ContinuousCDF, Normal};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+
+use crate::ML::diagnostics::{jarque_bera, ljung_box, TestResult};
+use crate::ML::forecaster::Forecaster;
 
 /// ARIMA model implementation
 pub struct ARIMA {
@@ -9,6 +16,55 @@ pub struct ARIMA {
     ar_params: Option<Array1<f64>>,  // AR parameters
     ma_params: Option<Array1<f64>>,  // MA parameters
     intercept: Option<f64>,  // Constant term
+    sigma2: Option<f64>,  // Residual variance
+    param_cov: Option<Array2<f64>>,  // Covariance of [intercept, AR, MA] estimates
+    history: Option<Array1<f64>>,  // Series the model was fitted on
+    state: Option<ForecastState>,  // End of the fitted series, enough to forecast from
+}
+
+/// Everything forecasting needs from the end of a series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ForecastState {
+    tails: Vec<f64>,  // Last value of every differencing level, see `difference`
+    lags: Vec<f64>,  // Last `p` differenced values, oldest first
+    residuals: Vec<f64>,  // Last `q` one-step residuals, oldest first
+}
+
+/// On-disk representation of a fitted model
+#[derive(Serialize, Deserialize)]
+struct SavedARIMA {
+    p: usize,
+    d: usize,
+    q: usize,
+    intercept: f64,
+    ar_params: Vec<f64>,
+    ma_params: Vec<f64>,
+    sigma2: f64,
+    state: ForecastState,
+}
+
+/// Estimate, standard error and significance of one fitted coefficient
+#[derive(Debug, Clone)]
+pub struct CoefficientSummary {
+    pub name: String,
+    pub estimate: f64,
+    pub std_error: f64,
+    pub z_value: f64,
+    pub p_value: f64,
+}
+
+/// Summary of a fitted ARIMA model and of its residual diagnostics
+#[derive(Debug, Clone)]
+pub struct ARIMASummary {
+    pub order: (usize, usize, usize),
+    pub nobs: usize,  // Residuals used in the fit
+    pub coefficients: Vec<CoefficientSummary>,
+    pub sigma2: f64,
+    pub log_likelihood: f64,
+    pub aic: f64,
+    pub bic: f64,
+    pub ljung_box: Option<TestResult>,  // None when the series is too short for the test
+    pub jarque_bera: Option<TestResult>,
 }
 
 impl ARIMA {
@@ -21,38 +77,46 @@ impl ARIMA {
             ar_params: None,
             ma_params: None,
             intercept: None,
+            sigma2: None,
+            param_cov: None,
+            history: None,
+            state: None,
         }
     }
     
-    /// Difference the time series to achieve stationarity
-    fn difference(&self, series: &Array1<f64>) -> Array1<f64> {
+    /// Difference the time series to achieve stationarity.
+    ///
+    /// Returns the differenced series together with the last value of every
+    /// intermediate series (level `0` is the original data), which is what
+    /// `undifference` needs to integrate forecasts back for any `d`.
+    fn difference(&self, series: &Array1<f64>) -> (Array1<f64>, Vec<f64>) {
         let mut result = series.clone();
+        let mut tails = Vec::with_capacity(self.d);
         for _ in 0..self.d {
             let n = result.len();
+            tails.push(result[n - 1]);
             let mut temp = Array1::zeros(n - 1);
             for i in 0..n - 1 {
                 temp[i] = result[i + 1] - result[i];
             }
             result = temp;
         }
-        result
+        (result, tails)
     }
     
-    /// Reverse differencing to get original scale predictions
-    fn undifference(&self, diff_preds: &Array1<f64>, orig_series: &Array1<f64>) -> Array1<f64> {
+    /// Reverse differencing to get original scale predictions.
+    ///
+    /// Integrates one level at a time, starting from the most differenced
+    /// series, each level anchored on the tail kept by `difference`.
+    fn undifference(&self, diff_preds: &Array1<f64>, tails: &[f64]) -> Array1<f64> {
         let mut result = diff_preds.clone();
         
-        for _ in 0..self.d {
-            let n = result.len();
-            let last_orig_idx = orig_series.len() - 1;
-            let mut temp = Array1::zeros(n + 1);
-            
-            temp[0] = orig_series[last_orig_idx];
-            for i in 0..n {
-                temp[i + 1] = temp[i] + result[i];
+        for &last in tails.iter().rev() {
+            let mut level = last;
+            for value in result.iter_mut() {
+                level += *value;
+                *value = level;
             }
-            
-            result = temp.slice(s![1..]).to_owned();
         }
         
         result
@@ -65,13 +129,14 @@ impl ARIMA {
         }
         
         // Perform differencing to achieve stationarity
-        let diff_data = self.difference(data);
+        let (diff_data, _) = self.difference(data);
         
-        // Construct matrices for OLS estimation
+        // Construct matrices for OLS estimation, the first row needs every AR and MA lag
         let n = diff_data.len();
-        let effective_n = n - self.p.max(self.q);
+        let start = self.p.max(self.q);
+        let effective_n = n - start;
         
-        if effective_n <= 0 {
+        if effective_n <= self.p + self.q + 1 {
             return Err("Not enough data points after accounting for lags".to_string());
         }
         
@@ -86,37 +151,42 @@ impl ARIMA {
         // Fill AR terms
         for i in 0..effective_n {
             for j in 0..self.p {
-                X[[i, j + 1]] = diff_data[i + self.p - j - 1];
+                X[[i, j + 1]] = diff_data[start + i - j - 1];
             }
         }
         
+        // Response vector y
+        let y = diff_data.slice(s![start..]).to_owned();
+        
         // Initialize residuals to zeros (will be updated iteratively)
         let mut residuals = Array1::zeros(n);
+        let mut coefficients = Array1::zeros(self.p + self.q + 1);
         
         // Iterative process for MA terms (since we don't know errors yet)
-        for _ in 0..5 {  // Limit iterations for simplicity
+        for iteration in 0..5 {  // Limit iterations for simplicity
             // Fill MA terms using current residuals
             for i in 0..effective_n {
                 for j in 0..self.q {
-                    let idx = i + self.p - j - 1;
-                    if idx < residuals.len() {
-                        X[[i, self.p + j + 1]] = residuals[idx];
-                    }
+                    X[[i, self.p + j + 1]] = residuals[start + i - j - 1];
                 }
             }
             
-            // Response vector y
-            let y = diff_data.slice(s![self.p..]).to_owned();
+            // The first pass has no residuals yet, so it fits the AR part only
+            // and leaves the MA coefficients at zero
+            let columns = if iteration == 0 { self.p + 1 } else { self.p + self.q + 1 };
+            let X_used = X.slice(s![.., ..columns]);
             
             // Solve OLS: coefficients = (X'X)^(-1) X'y
-            let X_t = X.t();
-            let X_t_X = X_t.dot(&X);
+            let X_t = X_used.t();
+            let X_t_X = X_t.dot(&X_used);
             let X_t_y = X_t.dot(&y);
             
-            let coefficients = match X_t_X.solve(&X_t_y) {
+            let solution = match X_t_X.solve(&X_t_y) {
                 Ok(c) => c,
                 Err(_) => return Err("Failed to solve linear system".to_string()),
             };
+            coefficients.fill(0.0);
+            coefficients.slice_mut(s![..columns]).assign(&solution);
             
             // Extract parameters
             self.intercept = Some(coefficients[0]);
@@ -140,6 +210,18 @@ impl ARIMA {
             }
         }
         
+        // Residual variance and coefficient covariance from the last regression
+        let regression_residuals = &y - &X.dot(&coefficients);
+        let dof = (effective_n - coefficients.len()) as f64;
+        let sigma2 = regression_residuals.dot(&regression_residuals) / dof;
+        let X_t_X_inv = X.t().dot(&X).inv()
+            .map_err(|_| "Failed to invert the information matrix".to_string())?;
+        
+        self.sigma2 = Some(sigma2);
+        self.param_cov = Some(X_t_X_inv * sigma2);
+        self.history = Some(data.clone());
+        self.state = Some(self.forecast_state(data)?);
+        
         Ok(())
     }
     
@@ -170,7 +252,7 @@ impl ARIMA {
             
             // Add MA component
             for j in 0..self.q {
-                if j < ma_params.len() && i >= j {
+                if j < ma_params.len() && i + self.p > j {
                     pred += ma_params[j] * residuals[i - j + self.p - 1];
                 }
             }
@@ -184,78 +266,601 @@ impl ARIMA {
         predictions
     }
     
+    /// One-step-ahead residuals of the fitted model.
+    ///
+    /// Residuals are computed on the differenced series, which equals the
+    /// one-step prediction error on the original scale. The first residual
+    /// corresponds to `data[d + p]`, earlier points have no complete set of lags.
+    pub fn residuals(&self, data: &Array1<f64>) -> Result<Array1<f64>, String> {
+        if !self.is_fitted() {
+            return Err("Model must be fitted before computing residuals".to_string());
+        }
+        if data.len() <= self.d + self.p {
+            return Err("Not enough data points to compute residuals".to_string());
+        }
+        
+        let (diff_data, _) = self.difference(data);
+        let fitted = self.predict_in_sample(&diff_data);
+        
+        Ok(&diff_data.slice(s![self.p..]) - &fitted)
+    }
+    
+    /// One-step-ahead fitted values on the original scale.
+    ///
+    /// Aligned with `residuals`: the first fitted value corresponds to `data[d + p]`.
+    pub fn fitted_values(&self, data: &Array1<f64>) -> Result<Array1<f64>, String> {
+        let residuals = self.residuals(data)?;
+        Ok(&data.slice(s![self.d + self.p..]) - &residuals)
+    }
+    
+    /// Summarise the fitted model: coefficient significance, information
+    /// criteria and residual diagnostics (Ljung-Box and Jarque-Bera).
+    pub fn summary(&self, data: &Array1<f64>) -> Result<ARIMASummary, String> {
+        let residuals = self.residuals(data)?;
+        let (sigma2, param_cov) = match (self.sigma2, &self.param_cov) {
+            (Some(sigma2), Some(cov)) => (sigma2, cov),
+            _ => return Err("Model must be fitted before summarising".to_string()),
+        };
+        
+        let mut estimates = vec![("intercept".to_string(), self.intercept.unwrap_or(0.0))];
+        if let Some(ar_params) = &self.ar_params {
+            for (j, &value) in ar_params.iter().enumerate() {
+                estimates.push((format!("ar.L{}", j + 1), value));
+            }
+        }
+        if let Some(ma_params) = &self.ma_params {
+            for (j, &value) in ma_params.iter().enumerate() {
+                estimates.push((format!("ma.L{}", j + 1), value));
+            }
+        }
+        
+        let normal = Normal::new(0.0, 1.0).map_err(|e| e.to_string())?;
+        let coefficients = estimates
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, estimate))| {
+                let std_error = param_cov[[i, i]].max(0.0).sqrt();
+                let z_value = estimate / std_error;
+                // A zero standard error gives an infinite z, or NaN for a zero estimate
+                let p_value = if z_value.is_finite() {
+                    2.0 * normal.cdf(-z_value.abs())
+                } else if z_value.is_nan() {
+                    f64::NAN
+                } else {
+                    0.0
+                };
+                CoefficientSummary { name, estimate, std_error, z_value, p_value }
+            })
+            .collect();
+        
+        // Conditional Gaussian log-likelihood with the ML variance estimate
+        let nobs = residuals.len();
+        let n = nobs as f64;
+        let sigma2_ml = residuals.dot(&residuals) / n;
+        let log_likelihood = -0.5 * n * ((2.0 * std::f64::consts::PI * sigma2_ml).ln() + 1.0);
+        let k = (self.p + self.q + 2) as f64;  // ARMA terms, intercept and variance
+        
+        let lags = 10.min(nobs / 2);
+        
+        Ok(ARIMASummary {
+            order: (self.p, self.d, self.q),
+            nobs,
+            coefficients,
+            sigma2,
+            log_likelihood,
+            aic: -2.0 * log_likelihood + 2.0 * k,
+            bic: -2.0 * log_likelihood + k * n.ln(),
+            ljung_box: ljung_box(&residuals, lags, self.p + self.q).ok(),
+            jarque_bera: jarque_bera(&residuals).ok(),
+        })
+    }
+    
     /// Forecast future values
     pub fn forecast(&self, data: &Array1<f64>, steps: usize) -> Result<Array1<f64>, String> {
         if !self.is_fitted() {
             return Err("Model must be fitted before forecasting".to_string());
         }
         
-        let diff_data = self.difference(data);
-        let n = diff_data.len();
-        
-        // Use stored parameters
-        let intercept = self.intercept.unwrap_or(0.0);
-        let ar_params = self.ar_params.clone().unwrap_or_else(|| Array1::zeros(0));
-        let ma_params = self.ma_params.clone().unwrap_or_else(|| Array1::zeros(0));
-        
-        // Latest observed values and errors for forecasting
-        let mut latest_values = Vec::new();
-        for i in (n - self.p.max(1))..n {
-            latest_values.push(diff_data[i]);
+        let state = self.forecast_state(data)?;
+        Ok(self.forecast_from_state(&state, steps))
+    }
+    
+    /// Collect what forecasting needs from the end of a series
+    fn forecast_state(&self, data: &Array1<f64>) -> Result<ForecastState, String> {
+        if data.len() <= self.d + self.p + self.q {
+            return Err("Not enough data points to forecast".to_string());
         }
         
-        // Calculate latest residuals
+        let (diff_data, tails) = self.difference(data);
+        let n = diff_data.len();
+        
+        // Latest residuals from the in-sample one-step predictions
         let fitted = self.predict_in_sample(&diff_data);
-        let mut latest_residuals = Vec::new();
-        for i in (fitted.len() - self.q.max(1))..fitted.len() {
-            latest_residuals.push(diff_data[i + self.p] - fitted[i]);
-        }
+        let m = fitted.len();
+        let residuals = (m - self.q..m).map(|i| diff_data[i + self.p] - fitted[i]).collect();
+        
+        Ok(ForecastState {
+            tails,
+            lags: diff_data.slice(s![n - self.p..]).to_vec(),
+            residuals,
+        })
+    }
+    
+    /// Forecast from the latest differenced values, residuals and differencing tails
+    fn forecast_from_state(&self, state: &ForecastState, steps: usize) -> Array1<f64> {
+        // Lags and residuals are extended with forecasts as we go, oldest first
+        let mut lags = state.lags.clone();
+        let mut residuals = state.residuals.clone();
         
         // Forecast on differenced data
         let mut diff_forecasts = Array1::zeros(steps);
         
         for i in 0..steps {
-            // Start with intercept
-            let mut forecast = intercept;
-            
-            // Add AR component
-            for j in 0..self.p {
-                if j < ar_params.len() {
-                    let idx = if i <= j {
-                        latest_values.len() - 1 - (j - i)
-                    } else {
-                        diff_forecasts.len() - 1 - (j - i)
-                    };
-                    
-                    let value = if i <= j {
-                        latest_values[idx]
-                    } else {
-                        diff_forecasts[i - j - 1]
-                    };
-                    
-                    forecast += ar_params[j] * value;
-                }
-            }
-            
-            // Add MA component (only using known errors, not forecasting errors)
-            for j in 0..self.q {
-                if j < ma_params.len() && j < latest_residuals.len() && i <= j {
-                    forecast += ma_params[j] * latest_residuals[latest_residuals.len() - 1 - (j - i)];
-                }
-            }
-            
+            let forecast = self.predict_next(&lags, &residuals);
             diff_forecasts[i] = forecast;
+            lags.push(forecast);
+            // Future errors are unknown, their expectation is zero
+            residuals.push(0.0);
         }
         
         // Transform forecasts back to original scale
-        let diff_forecasts_arr = diff_forecasts.clone();
-        let forecasts = self.undifference(&diff_forecasts_arr, data);
+        self.undifference(&diff_forecasts, &state.tails)
+    }
+    
+    /// One-step prediction of the differenced series from the latest lags and residuals
+    fn predict_next(&self, lags: &[f64], residuals: &[f64]) -> f64 {
+        // Start with intercept
+        let mut prediction = self.intercept.unwrap_or(0.0);
+        
+        // Add AR component
+        if let Some(ar_params) = &self.ar_params {
+            for j in 0..ar_params.len() {
+                prediction += ar_params[j] * lags[lags.len() - 1 - j];
+            }
+        }
+        
+        // Add MA component
+        if let Some(ma_params) = &self.ma_params {
+            for j in 0..ma_params.len() {
+                prediction += ma_params[j] * residuals[residuals.len() - 1 - j];
+            }
+        }
         
-        Ok(forecasts)
+        prediction
+    }
+    
+    /// Advance the forecast origin by one new observation.
+    ///
+    /// Differences the value through every level, computes its one-step
+    /// residual and shifts the lag and residual buffers, in O(d + p + q)
+    /// without touching the rest of the series. Parameters are unchanged and
+    /// the in-sample methods keep describing the original training series.
+    /// NaN and infinite values are rejected and leave the state untouched.
+    pub fn update(&mut self, value: f64) -> Result<(), String> {
+        if !self.is_fitted() {
+            return Err("Model must be fitted before updating".to_string());
+        }
+        if !value.is_finite() {
+            return Err("Value must be finite, impute the gap first".to_string());
+        }
+        let mut state = match self.state.take() {
+            Some(state) => state,
+            None => return Err("Model must be fitted before updating".to_string()),
+        };
+        
+        // Difference the new value, refreshing the tail of every level
+        let mut diff = value;
+        for tail in state.tails.iter_mut() {
+            let next = diff - *tail;
+            *tail = diff;
+            diff = next;
+        }
+        
+        let residual = diff - self.predict_next(&state.lags, &state.residuals);
+        
+        if self.p > 0 {
+            state.lags.remove(0);
+            state.lags.push(diff);
+        }
+        if self.q > 0 {
+            state.residuals.remove(0);
+            state.residuals.push(residual);
+        }
+        
+        self.state = Some(state);
+        Ok(())
+    }
+    
+    /// Psi weights of the MA(infinity) representation of the undifferenced model.
+    ///
+    /// The h-step forecast error variance is `sigma2 * sum(psi[0..h]^2)`.
+    fn psi_weights(&self, count: usize) -> Array1<f64> {
+        let ar_params = self.ar_params.clone().unwrap_or_else(|| Array1::zeros(0));
+        let ma_params = self.ma_params.clone().unwrap_or_else(|| Array1::zeros(0));
+        
+        // AR polynomial 1 - ar_1 B - ... multiplied by (1 - B)^d
+        let mut poly = vec![1.0];
+        poly.extend(ar_params.iter().map(|a| -a));
+        for _ in 0..self.d {
+            let mut next = poly.clone();
+            next.push(0.0);
+            for i in 1..next.len() {
+                next[i] -= poly[i - 1];
+            }
+            poly = next;
+        }
+        
+        let mut psi = Array1::zeros(count);
+        for j in 0..count {
+            let mut value = if j == 0 {
+                1.0
+            } else if j <= ma_params.len() {
+                ma_params[j - 1]
+            } else {
+                0.0
+            };
+            for i in 1..poly.len().min(j + 1) {
+                value -= poly[i] * psi[j - i];
+            }
+            psi[j] = value;
+        }
+        psi
+    }
+    
+    /// Model orders as `(p, d, q)`
+    pub fn order(&self) -> (usize, usize, usize) {
+        (self.p, self.d, self.q)
+    }
+    
+    /// Serialize the fitted model to JSON.
+    ///
+    /// Stores the orders, coefficients, residual variance and the state at the
+    /// end of the training series, which is all `from_json` needs to forecast
+    /// without the original data.
+    pub fn to_json(&self) -> Result<String, String> {
+        let (state, sigma2) = match (&self.state, self.sigma2) {
+            (Some(state), Some(sigma2)) if self.is_fitted() => (state.clone(), sigma2),
+            _ => return Err("Model must be fitted before saving".to_string()),
+        };
+        
+        let saved = SavedARIMA {
+            p: self.p,
+            d: self.d,
+            q: self.q,
+            intercept: self.intercept.unwrap_or(0.0),
+            ar_params: self.ar_params.as_ref().map(|a| a.to_vec()).unwrap_or_default(),
+            ma_params: self.ma_params.as_ref().map(|a| a.to_vec()).unwrap_or_default(),
+            sigma2,
+            state,
+        };
+        
+        serde_json::to_string_pretty(&saved).map_err(|e| e.to_string())
+    }
+    
+    /// Load a model saved with `to_json`, ready to forecast.
+    ///
+    /// The training series is not stored, so in-sample methods such as
+    /// `Forecaster::residuals` are unavailable until the model is refitted.
+    pub fn from_json(json: &str) -> Result<ARIMA, String> {
+        let saved: SavedARIMA = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        
+        if saved.ar_params.len() != saved.p
+            || saved.ma_params.len() != saved.q
+            || saved.state.tails.len() != saved.d
+            || saved.state.lags.len() != saved.p
+            || saved.state.residuals.len() != saved.q
+        {
+            return Err("Saved parameters do not match the model orders".to_string());
+        }
+        
+        let mut model = ARIMA::new(saved.p, saved.d, saved.q);
+        model.intercept = Some(saved.intercept);
+        model.ar_params = Some(Array1::from(saved.ar_params));
+        model.ma_params = Some(Array1::from(saved.ma_params));
+        model.sigma2 = Some(saved.sigma2);
+        model.state = Some(saved.state);
+        Ok(model)
+    }
+    
+    /// Save the fitted model as a JSON file
+    pub fn save(&self, file_path: &str) -> Result<(), String> {
+        let json = self.to_json()?;
+        fs::write(file_path, json).map_err(|e| e.to_string())
+    }
+    
+    /// Load a model from a JSON file written by `save`
+    pub fn load(file_path: &str) -> Result<ARIMA, String> {
+        let json = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+        ARIMA::from_json(&json)
     }
     
     /// Check if the model has been fitted
-    fn is_fitted(&self) -> bool {
+    pub fn is_fitted(&self) -> bool {
         self.ar_params.is_some() && self.ma_params.is_some() && self.intercept.is_some()
     }
-}
\ No newline at end of file
+}
+
+impl Forecaster for ARIMA {
+    fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+        ARIMA::fit(self, data)
+    }
+
+    fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        match &self.state {
+            Some(state) if self.is_fitted() => Ok(self.forecast_from_state(state, horizon)),
+            _ => Err("Model must be fitted before forecasting".to_string()),
+        }
+    }
+
+    fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        let sigma2 = match self.sigma2 {
+            Some(sigma2) => sigma2,
+            None => return Err("Model must be fitted before computing forecast variance".to_string()),
+        };
+        let psi = self.psi_weights(horizon);
+        let mut cumulative = 0.0;
+        Ok(psi.mapv(|w| {
+            cumulative += w * w;
+            sigma2 * cumulative
+        }))
+    }
+
+    fn fitted_values(&self) -> Result<Array1<f64>, String> {
+        match &self.history {
+            Some(history) => ARIMA::fitted_values(self, history),
+            None => Err("Model must be fitted before computing fitted values".to_string()),
+        }
+    }
+
+    fn residuals(&self) -> Result<Array1<f64>, String> {
+        match &self.history {
+            Some(history) => ARIMA::residuals(self, history),
+            None => Err("Model must be fitted before computing residuals".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ARIMASummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (p, d, q) = self.order;
+        writeln!(f, "ARIMA({}, {}, {}) - {} observations", p, d, q, self.nobs)?;
+        writeln!(f, "{}", "-".repeat(60))?;
+        writeln!(f, "{:<12}{:>12}{:>12}{:>12}{:>12}", "", "coef", "std err", "z", "P>|z|")?;
+        for c in &self.coefficients {
+            writeln!(
+                f,
+                "{:<12}{:>12.4}{:>12.4}{:>12.3}{:>12.3}",
+                c.name, c.estimate, c.std_error, c.z_value, c.p_value
+            )?;
+        }
+        writeln!(f, "{}", "-".repeat(60))?;
+        writeln!(f, "sigma2: {:.4}  log-likelihood: {:.3}", self.sigma2, self.log_likelihood)?;
+        writeln!(f, "AIC: {:.3}  BIC: {:.3}", self.aic, self.bic)?;
+        if let Some(lb) = &self.ljung_box {
+            writeln!(f, "Ljung-Box Q: {:.3} (df {}), p-value: {:.3}", lb.statistic, lb.df, lb.p_value)?;
+        }
+        if let Some(jb) = &self.jarque_bera {
+            writeln!(f, "Jarque-Bera: {:.3}, p-value: {:.3}", jb.statistic, jb.p_value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::uniform_noise;
+
+    /// Reference ARIMA(1,2,0) forecast written directly on the original scale:
+    /// (1 - phi B)(1 - B)^2 x_t = c expands to
+    /// x_t = c + (2 + phi) x_{t-1} - (1 + 2 phi) x_{t-2} + phi x_{t-3}
+    fn reference_arima_120(data: &[f64], c: f64, phi: f64, steps: usize) -> Vec<f64> {
+        let mut history = data.to_vec();
+        for _ in 0..steps {
+            let n = history.len();
+            let next = c + (2.0 + phi) * history[n - 1] - (1.0 + 2.0 * phi) * history[n - 2]
+                + phi * history[n - 3];
+            history.push(next);
+        }
+        history[data.len()..].to_vec()
+    }
+
+    #[test]
+    fn test_difference_and_undifference_roundtrip() {
+        let model = ARIMA::new(0, 2, 0);
+        let data = Array1::from(vec![1.0, 4.0, 9.0, 16.0, 25.0]);
+        let (diff, tails) = model.difference(&data);
+
+        assert_eq!(diff, Array1::from(vec![2.0, 2.0, 2.0]));
+        assert_eq!(tails, vec![25.0, 9.0]);
+
+        // Two more second differences of 2.0 continue the squares
+        let restored = model.undifference(&Array1::from(vec![2.0, 2.0]), &tails);
+        assert_eq!(restored, Array1::from(vec![36.0, 49.0]));
+    }
+
+    #[test]
+    fn test_forecast_d1_continues_linear_trend() {
+        let data = Array1::from((0..20).map(|t| 3.0 + 2.0 * t as f64).collect::<Vec<f64>>());
+        let mut model = ARIMA::new(0, 1, 0);
+        model.fit(&data).unwrap();
+
+        let forecasts = model.forecast(&data, 3).unwrap();
+        for (h, value) in forecasts.iter().enumerate() {
+            let expected = 3.0 + 2.0 * (20 + h) as f64;
+            assert!((value - expected).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_forecast_d2_continues_quadratic_trend() {
+        let data = Array1::from((0..20).map(|t| (t * t) as f64).collect::<Vec<f64>>());
+        let mut model = ARIMA::new(0, 2, 0);
+        model.fit(&data).unwrap();
+
+        let forecasts = model.forecast(&data, 4).unwrap();
+        for (h, value) in forecasts.iter().enumerate() {
+            let expected = ((20 + h) * (20 + h)) as f64;
+            assert!((value - expected).abs() < 1e-6, "step {}: {} vs {}", h, value, expected);
+        }
+    }
+
+    #[test]
+    fn test_forecast_d2_matches_reference() {
+        let data: Vec<f64> = (0..40)
+            .map(|t| {
+                let t = t as f64;
+                0.05 * t * t + 2.0 * (0.7 * t).sin() + 0.5 * (1.3 * t).cos()
+            })
+            .collect();
+        let series = Array1::from(data.clone());
+
+        let mut model = ARIMA::new(1, 2, 0);
+        model.fit(&series).unwrap();
+        let forecasts = model.forecast(&series, 6).unwrap();
+
+        let c = model.intercept.unwrap();
+        let phi = model.ar_params.as_ref().unwrap()[0];
+        let expected = reference_arima_120(&data, c, phi, 6);
+
+        for (value, reference) in forecasts.iter().zip(expected.iter()) {
+            assert!((value - reference).abs() < 1e-8, "{} vs {}", value, reference);
+        }
+    }
+
+    #[test]
+    fn test_fitted_values_and_residuals_reconstruct_data() {
+        let data: Vec<f64> = uniform_noise(30, 7)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 10.0 + (0.5 * t as f64).sin() + 0.1 * t as f64 + e)
+            .collect();
+        let series = Array1::from(data);
+        let mut model = ARIMA::new(2, 1, 1);
+        model.fit(&series).unwrap();
+
+        let fitted = model.fitted_values(&series).unwrap();
+        let residuals = model.residuals(&series).unwrap();
+        assert_eq!(fitted.len(), series.len() - 3);
+        assert_eq!(residuals.len(), fitted.len());
+
+        for i in 0..fitted.len() {
+            assert!((fitted[i] + residuals[i] - series[i + 3]).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let data: Vec<f64> = uniform_noise(60, 42)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 20.0 + 3.0 * (0.3 * t as f64).sin() + e)
+            .collect();
+        let series = Array1::from(data);
+        let mut model = ARIMA::new(1, 0, 1);
+        model.fit(&series).unwrap();
+
+        let summary = model.summary(&series).unwrap();
+        assert_eq!(summary.coefficients.len(), 3);
+        assert_eq!(summary.coefficients[1].name, "ar.L1");
+        assert_eq!(summary.nobs, 59);
+        assert!(summary.coefficients.iter().all(|c| c.std_error > 0.0));
+        assert!(summary.coefficients.iter().all(|c| (0.0..=1.0).contains(&c.p_value)));
+        assert!(summary.ljung_box.is_some());
+        assert!(summary.aic < summary.bic);
+
+        // A zero covariance gives p = 0, or NaN where the estimate is 0 as well
+        model.param_cov = Some(Array2::zeros((3, 3)));
+        model.ma_params = Some(Array1::zeros(1));
+        let summary = model.summary(&series).unwrap();
+        assert_eq!(summary.coefficients[0].p_value, 0.0);
+        assert_eq!(summary.coefficients[1].p_value, 0.0);
+        assert!(summary.coefficients[2].p_value.is_nan());
+    }
+
+    #[test]
+    fn test_residuals_require_fit() {
+        let model = ARIMA::new(1, 0, 0);
+        assert!(model.residuals(&Array1::from(vec![1.0, 2.0, 3.0])).is_err());
+    }
+
+    #[test]
+    fn test_json_roundtrip_forecasts_without_data() {
+        let data: Vec<f64> = uniform_noise(50, 3)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 22.0 + 0.05 * t as f64 + 2.0 * (0.4 * t as f64).sin() + e)
+            .collect();
+        let series = Array1::from(data);
+        let mut model = ARIMA::new(2, 1, 1);
+        model.fit(&series).unwrap();
+
+        let json = model.to_json().unwrap();
+        let loaded = ARIMA::from_json(&json).unwrap();
+
+        let expected = model.forecast(&series, 5).unwrap();
+        let forecasts = Forecaster::predict(&loaded, 5).unwrap();
+        for (a, b) in forecasts.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // The training data is not saved, so in-sample diagnostics need a refit
+        assert!(Forecaster::residuals(&loaded).is_err());
+    }
+
+    #[test]
+    fn test_from_json_rejects_mismatched_orders() {
+        let json = r#"{"p":2,"d":0,"q":0,"intercept":0.0,"ar_params":[0.5],"ma_params":[],
+            "sigma2":1.0,"state":{"tails":[],"lags":[1.0,2.0],"residuals":[]}}"#;
+        assert!(ARIMA::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_unfitted_model_cannot_be_saved() {
+        assert!(ARIMA::new(1, 0, 0).to_json().is_err());
+    }
+
+    #[test]
+    fn test_update_matches_batch_forecast() {
+        let data: Vec<f64> = uniform_noise(60, 11)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| 18.0 + 3.0 * (0.25 * t as f64).sin() + e)
+            .collect();
+        let series = Array1::from(data.clone());
+
+        let mut model = ARIMA::new(2, 1, 1);
+        model.fit(&series.slice(s![..45]).to_owned()).unwrap();
+
+        for (t, &value) in data.iter().enumerate().skip(45) {
+            // A missing reading is refused without disturbing the state
+            assert!(model.update(f64::NAN).is_err());
+            assert!(model.update(f64::INFINITY).is_err());
+            model.update(value).unwrap();
+            let online = Forecaster::predict(&model, 3).unwrap();
+            let batch = model.forecast(&series.slice(s![..t + 1]).to_owned(), 3).unwrap();
+            for (a, b) in online.iter().zip(batch.iter()) {
+                assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_psi_weights() {
+        // ARIMA(1,0,1): psi_1 = phi + theta, psi_j = phi * psi_{j-1}
+        let mut model = ARIMA::new(1, 0, 1);
+        model.ar_params = Some(Array1::from(vec![0.5]));
+        model.ma_params = Some(Array1::from(vec![0.3]));
+        let psi = model.psi_weights(4);
+        let expected = [1.0, 0.8, 0.4, 0.2];
+        for (a, b) in psi.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // ARIMA(0,2,0): psi_j = j + 1
+        let mut model = ARIMA::new(0, 2, 0);
+        model.ar_params = Some(Array1::zeros(0));
+        model.ma_params = Some(Array1::zeros(0));
+        assert_eq!(model.psi_weights(4), Array1::from(vec![1.0, 2.0, 3.0, 4.0]));
+    }
+}
@@ -0,0 +1,275 @@
+// src/ml/backtest.rs
+use ndarray::{s, Array1};
+
+use crate::ML::forecaster::Forecaster;
+
+/// How the training window moves between forecast origins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowType {
+    /// Train on every observation before the origin
+    Expanding,
+    /// Train on the last `initial_window` observations before the origin
+    Sliding,
+}
+
+/// Accuracy metrics for one forecast horizon, averaged over all origins
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonMetrics {
+    pub horizon: usize,  // Steps ahead, starting at 1
+    pub count: usize,  // Number of forecasts evaluated
+    pub mae: f64,
+    pub rmse: f64,
+    pub mape: f64,  // In percent, zero actuals are skipped
+    pub smape: f64,  // In percent
+    pub mase: f64,  // Scaled by the in-sample seasonal naive MAE of each training window, infinite when that MAE is 0, NaN if the forecast error is 0 as well
+}
+
+/// Forecasts made at every origin together with the per-horizon metrics
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub origins: Vec<usize>,  // Index of the first forecasted observation
+    pub forecasts: Vec<Array1<f64>>,
+    pub metrics: Vec<HorizonMetrics>,
+}
+
+/// Rolling-origin evaluation of a forecasting model.
+///
+/// At every origin the model is refitted on the training window and asked
+/// for `horizon` forecasts, which are compared against the held-out data.
+pub struct Backtest {
+    pub initial_window: usize,
+    pub horizon: usize,
+    pub step: usize,  // Observations between consecutive origins
+    pub window: WindowType,
+    pub season: usize,  // Seasonal period of the naive forecast used to scale MASE
+}
+
+impl Backtest {
+    /// Create a backtest that moves the origin one observation at a time
+    /// and scales MASE with the non-seasonal naive forecast.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_window` - Size of the first training window
+    /// * `horizon` - Number of steps forecast at each origin
+    /// * `window` - Whether the training window expands or slides
+    pub fn new(initial_window: usize, horizon: usize, window: WindowType) -> Self {
+        Backtest {
+            initial_window,
+            horizon,
+            step: 1,
+            window,
+            season: 1,
+        }
+    }
+
+    /// Run the backtest.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Any model implementing `Forecaster`, refitted at every origin
+    /// * `data` - The full series
+    ///
+    /// # Returns
+    ///
+    /// The forecasts made at each origin and the accuracy metrics per horizon
+    pub fn run<F: Forecaster + ?Sized>(&self, model: &mut F, data: &Array1<f64>) -> Result<BacktestReport, String> {
+        if self.horizon == 0 || self.step == 0 || self.season == 0 {
+            return Err("Horizon, step and season must be positive".to_string());
+        }
+        if self.initial_window <= self.season || self.initial_window >= data.len() {
+            return Err("Initial window must exceed the season and leave data to evaluate".to_string());
+        }
+
+        let n = data.len();
+        let mut origins = Vec::new();
+        let mut forecasts = Vec::new();
+        let mut errors: Vec<Vec<ForecastError>> = vec![Vec::new(); self.horizon];
+
+        let mut origin = self.initial_window;
+        while origin < n {
+            let start = match self.window {
+                WindowType::Expanding => 0,
+                WindowType::Sliding => origin - self.initial_window,
+            };
+            let train = data.slice(s![start..origin]).to_owned();
+
+            model.fit(&train)?;
+            let forecast = model.predict(self.horizon)?;
+            let scale = naive_scale(&train, self.season);
+
+            for h in 0..self.horizon.min(n - origin) {
+                errors[h].push(ForecastError {
+                    actual: data[origin + h],
+                    predicted: forecast[h],
+                    scale,
+                });
+            }
+
+            origins.push(origin);
+            forecasts.push(forecast);
+            origin += self.step;
+        }
+
+        let metrics = errors
+            .iter()
+            .enumerate()
+            .map(|(h, errors)| horizon_metrics(h + 1, errors))
+            .collect();
+
+        Ok(BacktestReport { origins, forecasts, metrics })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ForecastError {
+    actual: f64,
+    predicted: f64,
+    scale: f64,
+}
+
+/// In-sample MAE of the seasonal naive forecast, used to scale MASE.
+fn naive_scale(train: &Array1<f64>, season: usize) -> f64 {
+    let n = train.len();
+    let total: f64 = (season..n).map(|t| (train[t] - train[t - season]).abs()).sum();
+    total / (n - season) as f64
+}
+
+/// Mean of an iterator, NaN when it is empty. Infinite or NaN values are
+/// kept, so an undefined error shows in the metric rather than biasing it.
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        f64::NAN
+    } else {
+        sum / count as f64
+    }
+}
+
+fn horizon_metrics(horizon: usize, errors: &[ForecastError]) -> HorizonMetrics {
+    let abs_errors = || errors.iter().map(|e| (e.actual - e.predicted).abs());
+
+    HorizonMetrics {
+        horizon,
+        count: errors.len(),
+        mae: mean(abs_errors()),
+        rmse: mean(abs_errors().map(|e| e * e)).sqrt(),
+        mape: mean(
+            errors
+                .iter()
+                .filter(|e| e.actual != 0.0)
+                .map(|e| 100.0 * (e.actual - e.predicted).abs() / e.actual.abs()),
+        ),
+        smape: mean(errors.iter().map(|e| {
+            let total = e.actual.abs() + e.predicted.abs();
+            // Both zero is a perfect forecast
+            if total == 0.0 { 0.0 } else { 200.0 * (e.actual - e.predicted).abs() / total }
+        })),
+        mase: mean(errors.iter().map(|e| (e.actual - e.predicted).abs() / e.scale)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::arima::ARIMA;
+    use crate::ML::smoothing::SimpleExponentialSmoothing;
+
+    /// Forecasts the mean of the training window, handy for hand-checked metrics
+    struct MeanForecaster {
+        mean: Option<f64>,
+    }
+
+    impl Forecaster for MeanForecaster {
+        fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+            self.mean = data.mean();
+            Ok(())
+        }
+
+        fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+            Ok(Array1::from_elem(horizon, self.mean.unwrap()))
+        }
+
+        fn fitted_values(&self) -> Result<Array1<f64>, String> {
+            Err("Not needed for backtesting".to_string())
+        }
+
+        fn residuals(&self) -> Result<Array1<f64>, String> {
+            Err("Not needed for backtesting".to_string())
+        }
+    }
+
+    #[test]
+    fn test_metrics_by_hand() {
+        let data = Array1::from(vec![1.0, 3.0, 2.0, 4.0, 6.0]);
+        let mut model = MeanForecaster { mean: None };
+        let backtest = Backtest::new(3, 2, WindowType::Expanding);
+        let report = backtest.run(&mut model, &data).unwrap();
+
+        assert_eq!(report.origins, vec![3, 4]);
+
+        // Origin 3: mean 2.0, forecasts 4.0 and 6.0. Origin 4: mean 2.5, forecasts 6.0
+        let h1 = &report.metrics[0];
+        assert_eq!(h1.count, 2);
+        assert!((h1.mae - (2.0 + 3.5) / 2.0).abs() < 1e-12);
+        assert!((h1.rmse - ((4.0 + 12.25) / 2.0f64).sqrt()).abs() < 1e-12);
+        assert!((h1.mape - (50.0 + 100.0 * 3.5 / 6.0) / 2.0).abs() < 1e-12);
+
+        let h2 = &report.metrics[1];
+        assert_eq!(h2.count, 1);
+        assert!((h2.mae - 4.0).abs() < 1e-12);
+        assert!((h2.smape - 200.0 * 4.0 / 8.0).abs() < 1e-12);
+        // Naive in-sample MAE of [1, 3, 2] is 1.5
+        assert!((h2.mase - 4.0 / 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sliding_window_random_walk_on_trend() {
+        let data = Array1::from((0..30).map(|t| 5.0 + 0.5 * t as f64).collect::<Vec<f64>>());
+        let mut model = ARIMA::new(0, 1, 0);
+        let backtest = Backtest::new(10, 3, WindowType::Sliding);
+        let report = backtest.run(&mut model, &data).unwrap();
+
+        assert_eq!(report.origins.len(), 20);
+        for metrics in &report.metrics {
+            assert!(metrics.mae < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_compare_smoothing_model() {
+        let data = Array1::from(vec![10.0, 12.0, 11.0, 13.0, 12.0, 14.0, 13.0, 15.0]);
+        let mut model = SimpleExponentialSmoothing::new(0.5);
+        let backtest = Backtest::new(4, 1, WindowType::Expanding);
+        let report = backtest.run(&mut model, &data).unwrap();
+
+        assert_eq!(report.metrics[0].count, 4);
+        assert!(report.metrics[0].mae > 0.0);
+    }
+
+    #[test]
+    fn test_undefined_errors_are_not_dropped() {
+        // The first training window is constant, so its naive MAE is 0
+        let data = Array1::from(vec![5.0, 5.0, 5.0, 6.0, 0.0]);
+        let mut model = MeanForecaster { mean: None };
+        let report = Backtest::new(3, 1, WindowType::Expanding).run(&mut model, &data).unwrap();
+
+        let h1 = &report.metrics[0];
+        assert_eq!(h1.mase, f64::INFINITY);
+        // The zero actual is left out of MAPE only
+        assert!((h1.mape - 100.0 / 6.0).abs() < 1e-12);
+        assert!((h1.mae - (1.0 + 5.25) / 2.0).abs() < 1e-12);
+
+        // A perfect forecast of a constant window scales 0 by 0
+        let constant = Array1::from(vec![5.0; 4]);
+        let report = Backtest::new(3, 1, WindowType::Expanding).run(&mut model, &constant).unwrap();
+        assert!(report.metrics[0].mase.is_nan());
+    }
+
+    #[test]
+    fn test_invalid_window() {
+        let data = Array1::from(vec![1.0, 2.0, 3.0]);
+        let mut model = MeanForecaster { mean: None };
+        assert!(Backtest::new(3, 1, WindowType::Expanding).run(&mut model, &data).is_err());
+    }
+}
@@ -0,0 +1,548 @@
+// src/ml/changepoint.rs
+
+/// Scale factor turning a median absolute deviation into a normal standard deviation
+const MAD_SCALE: f64 = 1.4826;
+
+/// Which property of the series is allowed to change between segments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeType {
+    /// Shifts in the mean, with a common variance estimated from the first differences
+    Mean,
+    /// Changes in the variance around the overall mean
+    Variance,
+    /// Mean and variance change together
+    MeanAndVariance,
+}
+
+impl ChangeType {
+    /// Parameters estimated per segment, plus one for the change location
+    fn parameters(&self) -> f64 {
+        match self {
+            ChangeType::Mean | ChangeType::Variance => 2.0,
+            ChangeType::MeanAndVariance => 3.0,
+        }
+    }
+}
+
+/// Cost added for every change point, in units of -2 log-likelihood
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    /// Schwarz criterion, `parameters * ln(n)`
+    Bic,
+    /// Akaike criterion, `2 * parameters`
+    Aic,
+    /// A fixed value, larger means fewer changes
+    Manual(f64),
+}
+
+impl Penalty {
+    fn value(&self, change: ChangeType, n: usize) -> f64 {
+        match *self {
+            Penalty::Bic => change.parameters() * (n as f64).ln(),
+            Penalty::Aic => 2.0 * change.parameters(),
+            Penalty::Manual(value) => value,
+        }
+    }
+}
+
+/// Summary of the data between two change points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,  // Exclusive
+    pub mean: f64,
+    pub variance: f64,  // Sample variance, 0 for single values
+}
+
+/// Detected change points and the segments they delimit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangePoints {
+    pub changes: Vec<usize>,  // First index of every segment after the first
+    pub segments: Vec<Segment>,
+}
+
+impl ChangePoints {
+    fn new(data: &[f64], changes: Vec<usize>) -> Self {
+        let mut bounds = vec![0];
+        bounds.extend(changes.iter().copied());
+        bounds.push(data.len());
+
+        let segments = bounds
+            .windows(2)
+            .map(|w| {
+                let values = &data[w[0]..w[1]];
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let variance = if values.len() > 1 {
+                    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+                } else {
+                    0.0
+                };
+                Segment { start: w[0], end: w[1], mean, variance }
+            })
+            .collect();
+
+        ChangePoints { changes, segments }
+    }
+}
+
+/// Gaussian segment cost in O(1) from prefix sums
+struct SegmentCost {
+    change: ChangeType,
+    sums: Vec<f64>,
+    squares: Vec<f64>,
+    mean: f64,  // Overall mean, used by the variance cost
+    scale: f64,  // Common variance, used by the mean cost
+    floor: f64,  // Smallest variance allowed, keeps constant segments finite
+}
+
+impl SegmentCost {
+    fn new(data: &[f64], change: ChangeType) -> Self {
+        let n = data.len();
+        let mut sums = vec![0.0; n + 1];
+        let mut squares = vec![0.0; n + 1];
+        for (i, &y) in data.iter().enumerate() {
+            sums[i + 1] = sums[i] + y;
+            squares[i + 1] = squares[i] + y * y;
+        }
+        let mean = sums[n] / n as f64;
+        let variance = (squares[n] / n as f64 - mean * mean).max(0.0);
+
+        // Noise scale from the first differences is not inflated by the mean shifts
+        let mut differences: Vec<f64> = data.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+        differences.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let mad = if differences.is_empty() { 0.0 } else { differences[differences.len() / 2] };
+        let sigma = MAD_SCALE * mad / 2f64.sqrt();
+        let scale = if sigma > 0.0 {
+            sigma * sigma
+        } else if variance > 0.0 {
+            variance
+        } else {
+            1.0
+        };
+
+        SegmentCost {
+            change,
+            sums,
+            squares,
+            mean,
+            scale,
+            floor: 1e-10 * variance.max(1e-300),
+        }
+    }
+
+    /// Cost of `data[start..end]`
+    fn cost(&self, start: usize, end: usize) -> f64 {
+        let n = (end - start) as f64;
+        let sum = self.sums[end] - self.sums[start];
+        let squares = self.squares[end] - self.squares[start];
+
+        match self.change {
+            ChangeType::Mean => (squares - sum * sum / n).max(0.0) / self.scale,
+            ChangeType::Variance => {
+                let variance = (squares - 2.0 * self.mean * sum + n * self.mean * self.mean) / n;
+                n * variance.max(self.floor).ln()
+            }
+            ChangeType::MeanAndVariance => {
+                let variance = (squares - sum * sum / n) / n;
+                n * variance.max(self.floor).ln()
+            }
+        }
+    }
+}
+
+fn validate(data: &[f64], change: ChangeType, min_size: usize) -> Result<(), String> {
+    if min_size < 1 {
+        return Err("Minimum segment size must be at least 1".to_string());
+    }
+    // A single value has no spread, so its variance cost is degenerate
+    if change != ChangeType::Mean && min_size < 2 {
+        return Err("Variance changes need a minimum segment size of at least 2".to_string());
+    }
+    if data.len() < 2 * min_size {
+        return Err("Series is too short to contain a change point".to_string());
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err("Change-point detection requires finite values, impute gaps first".to_string());
+    }
+    Ok(())
+}
+
+/// Offline change-point detection with PELT (Killick et al., 2012).
+///
+/// Finds the segmentation minimising the total Gaussian cost plus `penalty`
+/// per change exactly, in close to linear time thanks to pruning.
+///
+/// # Arguments
+///
+/// * `data` - The series
+/// * `change` - Whether the mean, the variance or both change
+/// * `penalty` - Cost of adding a change point
+/// * `min_size` - Minimum number of observations per segment, at least 2 for variance changes
+///
+/// # Returns
+///
+/// The change indices with the statistics of every segment
+pub fn pelt(data: &[f64], change: ChangeType, penalty: Penalty, min_size: usize) -> Result<ChangePoints, String> {
+    validate(data, change, min_size)?;
+    let n = data.len();
+    let cost = SegmentCost::new(data, change);
+    let beta = penalty.value(change, n);
+
+    // best[t] is the optimal penalised cost of data[..t], last[t] its final change point
+    let mut best = vec![0.0; n + 1];
+    let mut last = vec![0usize; n + 1];
+    best[0] = -beta;
+    let mut candidates: Vec<usize> = vec![0];
+
+    for t in min_size..=n {
+        // Candidates become usable once they leave room for a full segment
+        if t >= 2 * min_size {
+            candidates.push(t - min_size);
+        }
+
+        let totals: Vec<(usize, f64)> = candidates
+            .iter()
+            .filter(|&&s| t - s >= min_size)
+            .map(|&s| (s, best[s] + cost.cost(s, t) + beta))
+            .collect();
+        let (arg, value) = totals
+            .iter()
+            .copied()
+            .fold((0, f64::INFINITY), |acc, x| if x.1 < acc.1 { x } else { acc });
+        best[t] = value;
+        last[t] = arg;
+
+        // Prune candidates that can never be optimal again
+        candidates.retain(|&s| t - s < min_size || best[s] + cost.cost(s, t) <= best[t]);
+    }
+
+    let mut changes = Vec::new();
+    let mut t = n;
+    while last[t] > 0 {
+        changes.push(last[t]);
+        t = last[t];
+    }
+    changes.reverse();
+
+    Ok(ChangePoints::new(data, changes))
+}
+
+/// Offline change-point detection by binary segmentation.
+///
+/// Repeatedly splits the segment whose best split lowers the cost the most,
+/// as long as the gain exceeds the penalty. Approximate but fast, and the
+/// number of changes can be capped.
+///
+/// # Arguments
+///
+/// * `data` - The series
+/// * `change` - Whether the mean, the variance or both change
+/// * `penalty` - Cost of adding a change point
+/// * `min_size` - Minimum number of observations per segment, at least 2 for variance changes
+/// * `max_changes` - Stop after this many changes, `None` for no limit
+pub fn binary_segmentation(
+    data: &[f64],
+    change: ChangeType,
+    penalty: Penalty,
+    min_size: usize,
+    max_changes: Option<usize>,
+) -> Result<ChangePoints, String> {
+    validate(data, change, min_size)?;
+    let n = data.len();
+    let cost = SegmentCost::new(data, change);
+    let beta = penalty.value(change, n);
+
+    // Best split of a segment and the cost it saves
+    let best_split = |start: usize, end: usize| -> Option<(usize, f64)> {
+        if end - start < 2 * min_size {
+            return None;
+        }
+        let whole = cost.cost(start, end);
+        (start + min_size..=end - min_size)
+            .map(|s| (s, whole - cost.cost(start, s) - cost.cost(s, end)))
+            .fold(None, |acc: Option<(usize, f64)>, x| match acc {
+                Some(a) if a.1 >= x.1 => Some(a),
+                _ => Some(x),
+            })
+    };
+
+    let mut segments = vec![(0, n)];
+    let mut changes = Vec::new();
+    while max_changes.is_none_or(|max| changes.len() < max) {
+        let candidate = segments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &(start, end))| best_split(start, end).map(|(s, gain)| (i, s, gain)))
+            .fold(None, |acc: Option<(usize, usize, f64)>, x| match acc {
+                Some(a) if a.2 >= x.2 => Some(a),
+                _ => Some(x),
+            });
+
+        match candidate {
+            Some((i, split, gain)) if gain > beta => {
+                let (start, end) = segments.remove(i);
+                segments.push((start, split));
+                segments.push((split, end));
+                changes.push(split);
+            }
+            _ => break,
+        }
+    }
+    changes.sort_unstable();
+
+    Ok(ChangePoints::new(data, changes))
+}
+
+/// Direction of a change flagged by `Cusum`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shift {
+    MeanUp,
+    MeanDown,
+    VarianceUp,
+    VarianceDown,
+}
+
+/// A change flagged by `Cusum`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CusumAlarm {
+    pub index: usize,  // Reading that crossed the threshold
+    pub start: usize,  // Estimated first reading of the new regime
+    pub shift: Shift,
+}
+
+/// One-sided cumulative sums of a standardised statistic
+#[derive(Debug, Clone, Copy, Default)]
+struct CusumPair {
+    upper: f64,
+    lower: f64,
+    upper_start: usize,
+    lower_start: usize,
+}
+
+impl CusumPair {
+    /// Accumulate `x` and report whether the upper or lower sum crossed `threshold`
+    fn update(&mut self, x: f64, index: usize, drift: f64, threshold: f64) -> Option<(bool, usize)> {
+        if self.upper <= 0.0 {
+            self.upper_start = index;
+        }
+        if self.lower <= 0.0 {
+            self.lower_start = index;
+        }
+        self.upper = (self.upper + x - drift).max(0.0);
+        self.lower = (self.lower - x - drift).max(0.0);
+
+        if self.upper > threshold {
+            Some((true, self.upper_start))
+        } else if self.lower > threshold {
+            Some((false, self.lower_start))
+        } else {
+            None
+        }
+    }
+}
+
+/// Online two-sided CUSUM (Page, 1954) for sensor streams.
+///
+/// The reference mean and standard deviation are learned from the first
+/// `warmup` readings, and learned again from the readings that follow every
+/// alarm so detection continues in the new regime. Mean changes accumulate
+/// standardised readings; variance changes accumulate the normalised square
+/// roots of their absolute values.
+pub struct Cusum {
+    pub change: ChangeType,
+    pub warmup: usize,
+    pub drift: f64,  // Slack per reading in standard deviations, half the smallest shift of interest
+    pub threshold: f64,  // Alarm level in standard deviations, 4 to 5 is usual
+    reference: Option<(f64, f64)>,  // Mean and standard deviation of the current regime
+    learning: Vec<f64>,
+    mean_sums: CusumPair,
+    variance_sums: CusumPair,
+    index: usize,
+}
+
+impl Cusum {
+    /// Create a CUSUM with drift 0.5 and threshold 5 standard deviations
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - Whether to watch the mean, the variance or both
+    /// * `warmup` - Readings used to learn each regime's mean and standard deviation
+    pub fn new(change: ChangeType, warmup: usize) -> Self {
+        Cusum {
+            change,
+            warmup,
+            drift: 0.5,
+            threshold: 5.0,
+            reference: None,
+            learning: Vec::with_capacity(warmup),
+            mean_sums: CusumPair::default(),
+            variance_sums: CusumPair::default(),
+            index: 0,
+        }
+    }
+
+    /// Start from a known mean and standard deviation instead of learning them
+    pub fn with_reference(mut self, mean: f64, std_dev: f64) -> Self {
+        self.reference = Some((mean, std_dev));
+        self
+    }
+
+    /// Feed the next reading, returning an alarm when a change is detected
+    pub fn update(&mut self, value: f64) -> Result<Option<CusumAlarm>, String> {
+        if self.warmup < 2 {
+            return Err("CUSUM needs at least two warm-up readings".to_string());
+        }
+        let index = self.index;
+        self.index += 1;
+        if value.is_nan() {
+            return Ok(None);
+        }
+
+        let (mean, std_dev) = match self.reference {
+            Some(reference) => reference,
+            None => {
+                self.learning.push(value);
+                if self.learning.len() == self.warmup {
+                    let n = self.warmup as f64;
+                    let mean = self.learning.iter().sum::<f64>() / n;
+                    let variance = self.learning.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                    self.reference = Some((mean, variance.sqrt().max(1e-12)));
+                    self.learning.clear();
+                }
+                return Ok(None);
+            }
+        };
+
+        let z = (value - mean) / std_dev;
+        let watch_mean = self.change != ChangeType::Variance;
+        let watch_variance = self.change != ChangeType::Mean;
+
+        let mut alarm = None;
+        if watch_mean {
+            if let Some((up, start)) = self.mean_sums.update(z, index, self.drift, self.threshold) {
+                let shift = if up { Shift::MeanUp } else { Shift::MeanDown };
+                alarm = Some(CusumAlarm { index, start, shift });
+            }
+        }
+        if watch_variance && alarm.is_none() {
+            // sqrt(|z|) is close to normal with mean 0.8222 and sd 0.3491 (Hawkins, 1981)
+            let x = (z.abs().sqrt() - 0.8222) / 0.3491;
+            if let Some((up, start)) = self.variance_sums.update(x, index, self.drift, self.threshold) {
+                let shift = if up { Shift::VarianceUp } else { Shift::VarianceDown };
+                alarm = Some(CusumAlarm { index, start, shift });
+            }
+        }
+
+        if alarm.is_some() {
+            // Learn the new regime from the readings after the alarm
+            self.reference = None;
+            self.mean_sums = CusumPair::default();
+            self.variance_sums = CusumPair::default();
+        }
+        Ok(alarm)
+    }
+
+    /// Forget the reference and every accumulated sum
+    pub fn reset(&mut self) {
+        self.reference = None;
+        self.learning.clear();
+        self.mean_sums = CusumPair::default();
+        self.variance_sums = CusumPair::default();
+        self.index = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::normal_noise;
+
+    /// Heater off, on, then off again
+    fn heater_series() -> Vec<f64> {
+        normal_noise(200, 7)
+            .iter()
+            .enumerate()
+            .map(|(t, e)| if (80..140).contains(&t) { 25.0 + 0.5 * e } else { 18.0 + 0.5 * e })
+            .collect()
+    }
+
+    #[test]
+    fn test_pelt_mean_changes() {
+        let data = heater_series();
+        let result = pelt(&data, ChangeType::Mean, Penalty::Bic, 2).unwrap();
+        assert_eq!(result.changes, vec![80, 140]);
+
+        assert_eq!(result.segments.len(), 3);
+        assert_eq!((result.segments[1].start, result.segments[1].end), (80, 140));
+        assert!((result.segments[1].mean - 25.0).abs() < 0.3);
+        assert!((result.segments[2].mean - 18.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_binary_segmentation_agrees_and_caps() {
+        let data = heater_series();
+        let result = binary_segmentation(&data, ChangeType::Mean, Penalty::Bic, 2, None).unwrap();
+        assert_eq!(result.changes, vec![80, 140]);
+
+        let capped = binary_segmentation(&data, ChangeType::Mean, Penalty::Bic, 2, Some(1)).unwrap();
+        assert_eq!(capped.changes.len(), 1);
+    }
+
+    #[test]
+    fn test_variance_change() {
+        let e = normal_noise(300, 19);
+        let data: Vec<f64> = (0..300).map(|t| if t < 150 { e[t] } else { 4.0 * e[t] }).collect();
+
+        for change in [ChangeType::Variance, ChangeType::MeanAndVariance] {
+            let result = pelt(&data, change, Penalty::Bic, 5).unwrap();
+            assert_eq!(result.changes.len(), 1);
+            assert!((result.changes[0] as i64 - 150).abs() <= 5, "{:?}", result.changes);
+            assert!(result.segments[1].variance > 10.0 * result.segments[0].variance);
+        }
+    }
+
+    #[test]
+    fn test_no_change_in_noise() {
+        let data = normal_noise(300, 21);
+        assert!(pelt(&data, ChangeType::Mean, Penalty::Bic, 2).unwrap().changes.is_empty());
+        assert_eq!(
+            pelt(&data, ChangeType::Mean, Penalty::Manual(0.0), 1).unwrap().changes.len(),
+            299
+        );
+    }
+
+    #[test]
+    fn test_cusum_detects_heater() {
+        let data = heater_series();
+        // A long warm-up learns each regime well enough to avoid false alarms
+        let mut cusum = Cusum::new(ChangeType::Mean, 50);
+        let alarms: Vec<CusumAlarm> = data.iter().filter_map(|&v| cusum.update(v).unwrap()).collect();
+
+        assert_eq!(alarms.len(), 2);
+        assert_eq!(alarms[0].shift, Shift::MeanUp);
+        assert!((alarms[0].start as i64 - 80).abs() <= 2);
+        assert!(alarms[0].index < 83);
+        assert_eq!(alarms[1].shift, Shift::MeanDown);
+        assert!((alarms[1].start as i64 - 140).abs() <= 2);
+    }
+
+    #[test]
+    fn test_cusum_variance() {
+        let e = normal_noise(200, 17);
+        let data: Vec<f64> = (0..200).map(|t| if t < 100 { e[t] } else { 3.0 * e[t] }).collect();
+        let mut cusum = Cusum::new(ChangeType::Variance, 10).with_reference(0.0, 1.0);
+        let alarm = data.iter().find_map(|&v| cusum.update(v).unwrap()).unwrap();
+
+        assert_eq!(alarm.shift, Shift::VarianceUp);
+        assert!(alarm.index >= 100 && alarm.index < 115, "index = {}", alarm.index);
+    }
+
+    #[test]
+    fn test_too_short() {
+        assert!(pelt(&[1.0, 2.0, 3.0], ChangeType::Mean, Penalty::Bic, 2).is_err());
+        assert!(pelt(&[1.0, f64::NAN, 3.0, 4.0], ChangeType::Mean, Penalty::Bic, 1).is_err());
+        assert!(pelt(&[1.0, 2.0, 3.0, 4.0], ChangeType::Variance, Penalty::Bic, 1).is_err());
+        assert!(binary_segmentation(&[1.0, 2.0, 3.0, 4.0], ChangeType::MeanAndVariance, Penalty::Bic, 1, None).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+// src/ml/correlation.rs
+use ndarray::{Array2, ArrayView1};
+
+use crate::ML::hypothesis::ranks;
+
+/// Correlation coefficient used by `correlation` and `correlation_matrix`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorrelationMethod {
+    /// Linear association
+    Pearson,
+    /// Pearson correlation of the ranks, any monotonic association
+    Spearman,
+    /// Kendall's tau-b, share of concordant minus discordant pairs, corrected for ties
+    Kendall,
+}
+
+/// Cross-correlation of two series at a range of lags
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossCorrelation {
+    pub lags: Vec<isize>,
+    pub values: Vec<f64>,  // Correlation of x[t + lag] with y[t]
+}
+
+impl CrossCorrelation {
+    /// Lag with the strongest correlation in absolute value, with its value
+    pub fn strongest(&self) -> Option<(isize, f64)> {
+        self.lags
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+            .filter(|(_, v)| !v.is_nan())
+            .max_by(|a, b| a.1.abs().partial_cmp(&b.1.abs()).unwrap())
+    }
+}
+
+/// Pairs where both values are observed
+fn complete_pairs(x: ArrayView1<f64>, y: ArrayView1<f64>) -> Result<(Vec<f64>, Vec<f64>), String> {
+    if x.len() != y.len() {
+        return Err("Series must have the same length".to_string());
+    }
+    Ok(x.iter()
+        .zip(y.iter())
+        .filter(|(a, b)| !a.is_nan() && !b.is_nan())
+        .map(|(&a, &b)| (a, b))
+        .unzip())
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn pearson_complete(x: &[f64], y: &[f64]) -> f64 {
+    let (mx, my) = (mean(x), mean(y));
+    let mut sxy = 0.0;
+    let mut sxx = 0.0;
+    let mut syy = 0.0;
+    for (a, b) in x.iter().zip(y.iter()) {
+        sxy += (a - mx) * (b - my);
+        sxx += (a - mx) * (a - mx);
+        syy += (b - my) * (b - my);
+    }
+    if sxx == 0.0 || syy == 0.0 {
+        return f64::NAN;
+    }
+    (sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0)
+}
+
+fn kendall_complete(x: &[f64], y: &[f64]) -> f64 {
+    let mut score = 0.0;
+    let mut untied_x = 0.0;
+    let mut untied_y = 0.0;
+    for i in 0..x.len() {
+        for j in i + 1..x.len() {
+            let dx = (x[i] - x[j]).signum() * ((x[i] != x[j]) as i32 as f64);
+            let dy = (y[i] - y[j]).signum() * ((y[i] != y[j]) as i32 as f64);
+            score += dx * dy;
+            untied_x += dx.abs();
+            untied_y += dy.abs();
+        }
+    }
+    if untied_x == 0.0 || untied_y == 0.0 {
+        return f64::NAN;
+    }
+    score / (untied_x * untied_y).sqrt()
+}
+
+/// Correlation between two series.
+///
+/// Pairs with a NaN on either side are left out, so columns straight from
+/// `read_sensor_table` can be compared without imputing first. Kendall's tau
+/// compares every pair of readings and costs O(n²).
+///
+/// # Arguments
+///
+/// * `x` - First series
+/// * `y` - Second series, same length
+/// * `method` - Correlation coefficient
+///
+/// # Returns
+///
+/// The coefficient in [-1, 1], NaN when either series is constant over the complete pairs
+pub fn correlation<'a>(
+    x: impl Into<ArrayView1<'a, f64>>,
+    y: impl Into<ArrayView1<'a, f64>>,
+    method: CorrelationMethod,
+) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x.into(), y.into())?;
+    if x.len() < 2 {
+        return Err("At least two complete pairs are needed".to_string());
+    }
+    Ok(match method {
+        CorrelationMethod::Pearson => pearson_complete(&x, &y),
+        CorrelationMethod::Spearman => pearson_complete(&ranks(&x).0, &ranks(&y).0),
+        CorrelationMethod::Kendall => kendall_complete(&x, &y),
+    })
+}
+
+/// Sample covariance (n - 1 denominator) of two series, over the complete pairs
+pub fn covariance<'a>(x: impl Into<ArrayView1<'a, f64>>, y: impl Into<ArrayView1<'a, f64>>) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x.into(), y.into())?;
+    if x.len() < 2 {
+        return Err("At least two complete pairs are needed".to_string());
+    }
+    let (mx, my) = (mean(&x), mean(&y));
+    let sum: f64 = x.iter().zip(y.iter()).map(|(a, b)| (a - mx) * (b - my)).sum();
+    Ok(sum / (x.len() - 1) as f64)
+}
+
+/// Apply a pairwise statistic to every pair of columns
+fn pairwise<'a>(
+    data: &'a Array2<f64>,
+    statistic: impl Fn(ArrayView1<'a, f64>, ArrayView1<'a, f64>) -> Result<f64, String>,
+) -> Result<Array2<f64>, String> {
+    let k = data.ncols();
+    let mut matrix = Array2::<f64>::zeros((k, k));
+    for i in 0..k {
+        for j in i..k {
+            let value = statistic(data.column(i), data.column(j))?;
+            matrix[[i, j]] = value;
+            matrix[[j, i]] = value;
+        }
+    }
+    Ok(matrix)
+}
+
+/// Correlation between every pair of columns of a table, such as one loaded
+/// with `read_sensor_table`. Each pair uses the rows where both columns are observed.
+///
+/// # Returns
+///
+/// A symmetric matrix with ones on the diagonal for non-constant columns
+pub fn correlation_matrix(data: &Array2<f64>, method: CorrelationMethod) -> Result<Array2<f64>, String> {
+    pairwise(data, |x, y| correlation(x, y, method))
+}
+
+/// Sample covariance between every pair of columns, each pair over the rows
+/// where both columns are observed.
+///
+/// With missing values the result is not guaranteed to be positive semi-definite.
+pub fn covariance_matrix(data: &Array2<f64>) -> Result<Array2<f64>, String> {
+    pairwise(data, covariance)
+}
+
+/// Cross-correlation of two series for lags `-max_lag..=max_lag`.
+///
+/// The value at lag k estimates the correlation of `x[t + k]` with `y[t]`,
+/// so a peak at a positive lag means `x` follows `y` (as in R's `ccf`).
+/// Both series are centred on their full means and normalised by `n`, the
+/// usual estimator that keeps far lags from being overstated. NaN readings
+/// are left out of every product they appear in.
+///
+/// # Arguments
+///
+/// * `x` - First series
+/// * `y` - Second series, same length
+/// * `max_lag` - Largest lag in either direction, smaller than the length
+///
+/// # Returns
+///
+/// The lags in increasing order with their correlations
+pub fn cross_correlation<'a>(
+    x: impl Into<ArrayView1<'a, f64>>,
+    y: impl Into<ArrayView1<'a, f64>>,
+    max_lag: usize,
+) -> Result<CrossCorrelation, String> {
+    let (x, y) = (x.into(), y.into());
+    let n = x.len();
+    if n != y.len() {
+        return Err("Series must have the same length".to_string());
+    }
+    if max_lag >= n {
+        return Err("Maximum lag must be smaller than the series length".to_string());
+    }
+    let observed = |s: ArrayView1<f64>| s.iter().copied().filter(|v| !v.is_nan()).collect::<Vec<f64>>();
+    let (xs, ys) = (observed(x), observed(y));
+    if xs.len() < 2 || ys.len() < 2 {
+        return Err("At least two observed values per series are needed".to_string());
+    }
+    let (mx, my) = (mean(&xs), mean(&ys));
+    let sx = (xs.iter().map(|v| (v - mx).powi(2)).sum::<f64>() / xs.len() as f64).sqrt();
+    let sy = (ys.iter().map(|v| (v - my).powi(2)).sum::<f64>() / ys.len() as f64).sqrt();
+
+    let mut lags = Vec::with_capacity(2 * max_lag + 1);
+    let mut values = Vec::with_capacity(2 * max_lag + 1);
+    for lag in -(max_lag as isize)..=max_lag as isize {
+        let mut sum = 0.0;
+        for t in 0..n {
+            let shifted = t as isize + lag;
+            if shifted < 0 || shifted >= n as isize {
+                continue;
+            }
+            let (a, b) = (x[shifted as usize], y[t]);
+            if !a.is_nan() && !b.is_nan() {
+                sum += (a - mx) * (b - my);
+            }
+        }
+        lags.push(lag);
+        values.push(sum / (n as f64 * sx * sy));
+    }
+    Ok(CrossCorrelation { lags, values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array1;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_pairwise_coefficients() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let y = [2.0, 1.0, 4.0, 3.0, 7.0, 5.0];
+        // Hand-computed: Pearson 0.7918, Spearman 1 - 6 * 6 / 210, Kendall (12 - 3) / 15
+        assert!(close(correlation(&x[..], &y[..], CorrelationMethod::Pearson).unwrap(), 0.791795));
+        assert!(close(correlation(&x[..], &y[..], CorrelationMethod::Spearman).unwrap(), 1.0 - 36.0 / 210.0));
+        assert!(close(correlation(&x[..], &y[..], CorrelationMethod::Kendall).unwrap(), 0.6));
+        assert!(close(covariance(&x[..], &y[..]).unwrap(), 3.2));
+
+        // Monotonic but non-linear: ranks see a perfect relationship
+        let cubes: Vec<f64> = x.iter().map(|v| v * v * v).collect();
+        assert!(close(correlation(&x[..], &cubes[..], CorrelationMethod::Spearman).unwrap(), 1.0));
+        assert!(close(correlation(&x[..], &cubes[..], CorrelationMethod::Kendall).unwrap(), 1.0));
+        assert!(correlation(&x[..], &cubes[..], CorrelationMethod::Pearson).unwrap() < 1.0);
+
+        // Ties use tau-b
+        let tied = [1.0, 1.0, 2.0, 3.0];
+        let other = [1.0, 2.0, 3.0, 4.0];
+        assert!(close(correlation(&tied[..], &other[..], CorrelationMethod::Kendall).unwrap(), 5.0 / 30f64.sqrt()));
+
+        assert!(correlation(&x[..], &y[..3], CorrelationMethod::Pearson).is_err());
+        assert!(correlation(&x[..], &[1.0; 6][..], CorrelationMethod::Pearson).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_matrices_on_sensor_table() {
+        let mut data = Array2::<f64>::zeros((50, 3));
+        for i in 0..50 {
+            let t = i as f64;
+            data[[i, 0]] = 20.0 + 0.1 * t;
+            data[[i, 1]] = 70.0 - 0.3 * t + (t * 1.3).sin();
+            data[[i, 2]] = (t * 0.7).cos();
+        }
+        data[[10, 1]] = f64::NAN;
+
+        let correlations = correlation_matrix(&data, CorrelationMethod::Pearson).unwrap();
+        assert!(close(correlations[[0, 0]], 1.0));
+        assert!(correlations[[0, 1]] < -0.95);
+        assert_eq!(correlations[[0, 1]], correlations[[1, 0]]);
+        assert!(correlations[[0, 2]].abs() < 0.2);
+
+        let covariances = covariance_matrix(&data).unwrap();
+        let column = data.column(0).to_vec();
+        let m = mean(&column);
+        let variance = column.iter().map(|v| (v - m).powi(2)).sum::<f64>() / 49.0;
+        assert!(close(covariances[[0, 0]], variance));
+        assert!(covariances[[0, 1]] < 0.0);
+    }
+
+    #[test]
+    fn test_cross_correlation_finds_lag() {
+        // Humidity responds to temperature three samples later
+        let n = 200;
+        let temperature: Array1<f64> = (0..n).map(|i| (i as f64 * 0.15).sin() + (i as f64 * 0.41).cos()).collect();
+        let humidity: Array1<f64> = (0..n).map(|i| if i >= 3 { -temperature[i - 3] } else { 0.0 }).collect();
+
+        let ccf = cross_correlation(&humidity, &temperature, 10).unwrap();
+        assert_eq!(ccf.lags.len(), 21);
+        assert_eq!(ccf.lags[0], -10);
+        let (lag, value) = ccf.strongest().unwrap();
+        assert_eq!(lag, 3);
+        assert!(value < -0.9);
+
+        // At lag 0 the definition reduces to the Pearson correlation on the full means
+        let same = cross_correlation(&temperature, &temperature, 2).unwrap();
+        assert!(close(same.values[2], 1.0));
+        assert!(cross_correlation(&temperature, &humidity, n).is_err());
+    }
+}
@@ -1,7 +1,19 @@
 // src/ml/data_imputation.rs
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::ML::decomposition::{classical_decomposition, DecompositionModel};
+use crate::ML::forecaster::Forecaster;
+use crate::ML::kalman::LocalLevel;
+
+/// Passes of seasonal estimation in `ImputationStrategy::Seasonal`
+const SEASONAL_REFINEMENTS: usize = 5;
 
 /// Linear interpolation for a missing value in a list.
 ///
+/// Both neighbours must be present; use `impute` to fill whole series with gaps.
+///
 /// # Arguments
 ///
 /// * `values` - A slice of values
@@ -45,6 +57,8 @@ pub fn simple_exponential_smoothing(values: &[f64], alpha: f64) -> Vec<f64> {
 
 /// Calculate the median values for segments of the data.
 ///
+/// Segments do not overlap; use `Rolling::median` for sliding windows.
+///
 /// # Arguments
 ///
 /// * `data` - A slice of values
@@ -77,6 +91,221 @@ pub fn median(data: &[f64], time_interval: usize) -> Vec<f64> {
     result
 }
 
+/// How `impute` fills missing values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImputationStrategy {
+    /// Straight line between the observed values on either side of the gap
+    Linear,
+    /// Last observation carried forward (LOCF)
+    Previous,
+    /// Next observation carried backward (NOCB)
+    Next,
+    /// Mean of the observed values
+    Mean,
+    /// Median of the observed values
+    Median,
+    /// A fixed value
+    Constant(f64),
+    /// Smoothed level of a local-level model fitted by maximum likelihood
+    Kalman,
+    /// Remove the classical seasonal component, interpolate linearly and add it back
+    Seasonal { period: usize },
+}
+
+/// A completed series together with the positions that were filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Imputed {
+    pub values: Vec<f64>,
+    pub imputed: Vec<usize>,  // Indices that were missing, in increasing order
+}
+
+/// Fill every missing value of a series.
+///
+/// Runs of consecutive gaps are handled as a whole. Gaps at the start or end
+/// have an observed value on one side only, so `Linear`, `Previous` and `Next`
+/// fill them with the nearest observed value.
+///
+/// # Arguments
+///
+/// * `values` - The series, `None` marks a missing value
+/// * `strategy` - How to fill the gaps
+///
+/// # Returns
+///
+/// The completed series and the imputed positions
+pub fn impute(values: &[Option<f64>], strategy: ImputationStrategy) -> Result<Imputed, String> {
+    let observed: Vec<f64> = values.iter().flatten().copied().collect();
+    if observed.is_empty() {
+        if let ImputationStrategy::Constant(fill) = strategy {
+            return Ok(Imputed { values: vec![fill; values.len()], imputed: (0..values.len()).collect() });
+        }
+        return Err("Cannot impute a series without observed values".to_string());
+    }
+
+    match strategy {
+        ImputationStrategy::Kalman => return kalman_impute(values),
+        ImputationStrategy::Seasonal { period } => return seasonal_impute(values, period),
+        _ => {}
+    }
+
+    let overall = match strategy {
+        ImputationStrategy::Mean => observed.iter().sum::<f64>() / observed.len() as f64,
+        ImputationStrategy::Median => median(&observed, observed.len())[0],
+        ImputationStrategy::Constant(fill) => fill,
+        _ => f64::NAN,
+    };
+
+    // Nearest observed position at or after every index
+    let mut next_observed = vec![None; values.len()];
+    let mut upcoming = None;
+    for i in (0..values.len()).rev() {
+        if values[i].is_some() {
+            upcoming = Some(i);
+        }
+        next_observed[i] = upcoming;
+    }
+
+    let mut result = Vec::with_capacity(values.len());
+    let mut imputed = Vec::new();
+    let mut previous: Option<usize> = None;
+    for (i, value) in values.iter().enumerate() {
+        if let Some(v) = value {
+            result.push(*v);
+            previous = Some(i);
+            continue;
+        }
+
+        let before = previous.map(|j| (j, values[j].unwrap_or(f64::NAN)));
+        let after = next_observed[i].map(|j| (j, values[j].unwrap_or(f64::NAN)));
+        let fill = match (strategy, before, after) {
+            (ImputationStrategy::Linear, Some((j0, y0)), Some((j1, y1))) => {
+                y0 + (y1 - y0) * (i - j0) as f64 / (j1 - j0) as f64
+            }
+            (ImputationStrategy::Previous, Some((_, y0)), _) => y0,
+            (ImputationStrategy::Next, _, Some((_, y1))) => y1,
+            (ImputationStrategy::Linear | ImputationStrategy::Previous | ImputationStrategy::Next, _, _) => {
+                // Edge gap: only one side is observed
+                before.or(after).map(|(_, y)| y).unwrap_or(f64::NAN)
+            }
+            _ => overall,
+        };
+        result.push(fill);
+        imputed.push(i);
+    }
+
+    Ok(Imputed { values: result, imputed })
+}
+
+/// `impute` for series that mark missing values with NaN
+pub fn impute_nan(values: &[f64], strategy: ImputationStrategy) -> Result<Imputed, String> {
+    let options: Vec<Option<f64>> = values.iter().map(|&v| if v.is_nan() { None } else { Some(v) }).collect();
+    impute(&options, strategy)
+}
+
+fn missing_positions(values: &[Option<f64>]) -> Vec<usize> {
+    values.iter().enumerate().filter(|(_, v)| v.is_none()).map(|(i, _)| i).collect()
+}
+
+fn kalman_impute(values: &[Option<f64>]) -> Result<Imputed, String> {
+    let data: Array1<f64> = values.iter().map(|v| v.unwrap_or(f64::NAN)).collect();
+    let mut model = LocalLevel::new();
+    model.fit(&data)?;
+    let level = model.smoothed_level()?;
+
+    let filled = values.iter().enumerate().map(|(i, v)| v.unwrap_or(level[i])).collect();
+    Ok(Imputed { values: filled, imputed: missing_positions(values) })
+}
+
+fn seasonal_impute(values: &[Option<f64>], period: usize) -> Result<Imputed, String> {
+    // The seasonal component is estimated on a filled copy, refined a few times
+    // so the provisional linear fill does not bias it
+    let mut result = impute(values, ImputationStrategy::Linear)?;
+    for _ in 0..SEASONAL_REFINEMENTS {
+        let seasonal = classical_decomposition(&result.values, period, DecompositionModel::Additive)?.seasonal;
+        let adjusted: Vec<Option<f64>> = values.iter().enumerate().map(|(i, v)| v.map(|y| y - seasonal[i])).collect();
+        result = impute(&adjusted, ImputationStrategy::Linear)?;
+        for (value, s) in result.values.iter_mut().zip(seasonal.iter()) {
+            *value += s;
+        }
+    }
+    Ok(result)
+}
+
+/// Accuracy of one strategy on artificially masked values
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImputationScore {
+    pub strategy: ImputationStrategy,
+    pub rmse: f64,
+    pub masked: usize,  // Number of known values hidden from the strategy
+}
+
+/// Compare imputation strategies by hiding known values and measuring how
+/// well each strategy recovers them.
+///
+/// Gaps of `gap_length` consecutive observed values are masked at random
+/// until about `mask_fraction` of the observed values are hidden.
+///
+/// # Arguments
+///
+/// * `values` - The series, NaN marks values that are already missing
+/// * `strategies` - Strategies to compare
+/// * `mask_fraction` - Share of the observed values to hide, in (0, 1)
+/// * `gap_length` - Length of every masked gap, 1 for isolated values
+/// * `seed` - Seed of the random masking, so runs are repeatable
+///
+/// # Returns
+///
+/// The RMSE on the masked values for every strategy, in the given order
+pub fn evaluate_imputation(
+    values: &[f64],
+    strategies: &[ImputationStrategy],
+    mask_fraction: f64,
+    gap_length: usize,
+    seed: u64,
+) -> Result<Vec<ImputationScore>, String> {
+    if !(mask_fraction > 0.0 && mask_fraction < 1.0) {
+        return Err("Mask fraction must be in (0, 1)".to_string());
+    }
+    if gap_length == 0 || gap_length > values.len() {
+        return Err("Gap length must be between 1 and the length of the series".to_string());
+    }
+
+    let observed = values.iter().filter(|v| !v.is_nan()).count();
+    let target = ((observed as f64 * mask_fraction).round() as usize).max(1);
+    let mut masked_values = values.to_vec();
+    let mut masked = Vec::new();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut attempts = 0;
+    while masked.len() < target && attempts < 100 * values.len() {
+        attempts += 1;
+        let start = rng.gen_range(0..=values.len() - gap_length);
+        let gap = start..start + gap_length;
+        if gap.clone().all(|i| !masked_values[i].is_nan()) {
+            for i in gap {
+                masked_values[i] = f64::NAN;
+                masked.push(i);
+            }
+        }
+    }
+    if masked.is_empty() {
+        return Err("No observed values could be masked".to_string());
+    }
+
+    strategies
+        .iter()
+        .map(|&strategy| {
+            let filled = impute_nan(&masked_values, strategy)?;
+            let squared: f64 = masked.iter().map(|&i| (filled.values[i] - values[i]).powi(2)).sum();
+            Ok(ImputationScore {
+                strategy,
+                rmse: (squared / masked.len() as f64).sqrt(),
+                masked: masked.len(),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,4 +335,77 @@ mod tests {
         assert_eq!(result[0], 4.0);  // median of [1.0, 5.0, 3.0, 7.0]
         assert_eq!(result[1], 6.0);  // median of [9.0, 2.0, 4.0, 8.0]
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_impute_linear_runs_and_edges() {
+        let values = [None, Some(1.0), None, None, Some(4.0), Some(5.0), None];
+        let result = impute(&values, ImputationStrategy::Linear).unwrap();
+        assert_eq!(result.values, vec![1.0, 1.0, 2.0, 3.0, 4.0, 5.0, 5.0]);
+        assert_eq!(result.imputed, vec![0, 2, 3, 6]);
+    }
+
+    #[test]
+    fn test_impute_carry_strategies() {
+        let values = [f64::NAN, 2.0, f64::NAN, 6.0, f64::NAN];
+        let previous = impute_nan(&values, ImputationStrategy::Previous).unwrap();
+        assert_eq!(previous.values, vec![2.0, 2.0, 2.0, 6.0, 6.0]);
+        let next = impute_nan(&values, ImputationStrategy::Next).unwrap();
+        assert_eq!(next.values, vec![2.0, 2.0, 6.0, 6.0, 6.0]);
+        let mean = impute_nan(&values, ImputationStrategy::Mean).unwrap();
+        assert_eq!(mean.values, vec![4.0, 2.0, 4.0, 6.0, 4.0]);
+    }
+
+    #[test]
+    fn test_impute_all_missing() {
+        let values = [None, None];
+        assert!(impute(&values, ImputationStrategy::Linear).is_err());
+        let constant = impute(&values, ImputationStrategy::Constant(0.0)).unwrap();
+        assert_eq!(constant.values, vec![0.0, 0.0]);
+        assert_eq!(constant.imputed, vec![0, 1]);
+    }
+
+    fn seasonal_series() -> Vec<f64> {
+        (0..96)
+            .map(|t| 20.0 + 0.05 * t as f64 + 3.0 * (2.0 * std::f64::consts::PI * t as f64 / 12.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_seasonal_imputation_of_long_gap() {
+        let truth = seasonal_series();
+        let mut values = truth.clone();
+        for v in values.iter_mut().skip(40).take(8) {
+            *v = f64::NAN;
+        }
+
+        let seasonal = impute_nan(&values, ImputationStrategy::Seasonal { period: 12 }).unwrap();
+        let linear = impute_nan(&values, ImputationStrategy::Linear).unwrap();
+        assert_eq!(seasonal.imputed, (40..48).collect::<Vec<usize>>());
+
+        let error = |filled: &[f64]| (40..48).map(|i| (filled[i] - truth[i]).abs()).fold(0.0, f64::max);
+        assert!(error(&seasonal.values) < 0.3);
+        assert!(error(&linear.values) > 2.0);
+    }
+
+    #[test]
+    fn test_kalman_imputation() {
+        let values = [Some(1.0), Some(1.2), None, None, Some(2.0), Some(2.1), Some(1.9), None, Some(2.2), Some(2.3)];
+        let result = impute(&values, ImputationStrategy::Kalman).unwrap();
+        assert_eq!(result.imputed, vec![2, 3, 7]);
+        assert_eq!(result.values[0], 1.0);
+        assert!(result.values[2] > 1.0 && result.values[3] < 2.2);
+        assert!(result.values[7] > 1.8 && result.values[7] < 2.3);
+    }
+
+    #[test]
+    fn test_evaluate_imputation() {
+        let values = seasonal_series();
+        let strategies = [ImputationStrategy::Linear, ImputationStrategy::Seasonal { period: 12 }, ImputationStrategy::Mean];
+        let scores = evaluate_imputation(&values, &strategies, 0.2, 6, 42).unwrap();
+
+        assert_eq!(scores.len(), 3);
+        assert!(scores[0].masked >= 18);
+        assert!(scores[1].rmse < scores[0].rmse);
+        assert!(scores.iter().all(|score| score.rmse.is_finite()));
+    }
+}
@@ -0,0 +1,388 @@
+// src/ml/decomposition.rs
+use ndarray::{Array1, ArrayView1};
+
+/// How the seasonal component combines with the trend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecompositionModel {
+    /// y = trend + seasonal + remainder
+    Additive,
+    /// y = trend * seasonal * remainder
+    Multiplicative,
+}
+
+/// Trend, seasonal and remainder components of a series
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    pub trend: Array1<f64>,
+    pub seasonal: Array1<f64>,
+    pub remainder: Array1<f64>,
+    pub model: DecompositionModel,
+}
+
+impl Decomposition {
+    /// The series with the seasonal component removed
+    pub fn seasonally_adjusted(&self) -> Array1<f64> {
+        match self.model {
+            DecompositionModel::Additive => &self.trend + &self.remainder,
+            DecompositionModel::Multiplicative => &self.trend * &self.remainder,
+        }
+    }
+}
+
+/// Classical decomposition by moving averages.
+///
+/// The trend is a centred moving average over one period (2 x m for even
+/// periods), so the first and last `period / 2` trend and remainder values are
+/// NaN. Seasonal indices are the per-position averages of the detrended
+/// series, normalised to sum to zero (additive) or average one (multiplicative).
+///
+/// # Arguments
+///
+/// * `data` - The series, as an `Array1`, a slice or a `Vec`
+/// * `period` - Seasonal period in samples
+/// * `model` - Additive or multiplicative combination
+pub fn classical_decomposition<'a>(
+    data: impl Into<ArrayView1<'a, f64>>,
+    period: usize,
+    model: DecompositionModel,
+) -> Result<Decomposition, String> {
+    let data = data.into();
+    let n = data.len();
+    if period < 2 {
+        return Err("Seasonal period must be at least 2".to_string());
+    }
+    if n < 2 * period {
+        return Err("At least two full periods are needed for the decomposition".to_string());
+    }
+    if model == DecompositionModel::Multiplicative && data.iter().any(|&y| y <= 0.0) {
+        return Err("Multiplicative decomposition requires strictly positive data".to_string());
+    }
+
+    // Centred moving average, half weights at both ends for even periods
+    let half = period / 2;
+    let mut trend = Array1::from_elem(n, f64::NAN);
+    for t in half..n - half {
+        trend[t] = if period % 2 == 0 {
+            let inner: f64 = (t - half + 1..t + half).map(|i| data[i]).sum();
+            (inner + 0.5 * (data[t - half] + data[t + half])) / period as f64
+        } else {
+            (t - half..=t + half).map(|i| data[i]).sum::<f64>() / period as f64
+        };
+    }
+
+    let detrended = match model {
+        DecompositionModel::Additive => &data - &trend,
+        DecompositionModel::Multiplicative => &data / &trend,
+    };
+
+    // Average the detrended values at each seasonal position
+    let mut indices = vec![0.0; period];
+    for (position, index) in indices.iter_mut().enumerate() {
+        let values: Vec<f64> = detrended
+            .iter()
+            .skip(position)
+            .step_by(period)
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect();
+        *index = values.iter().sum::<f64>() / values.len() as f64;
+    }
+    let mean_index = indices.iter().sum::<f64>() / period as f64;
+    for index in indices.iter_mut() {
+        match model {
+            DecompositionModel::Additive => *index -= mean_index,
+            DecompositionModel::Multiplicative => *index /= mean_index,
+        }
+    }
+
+    let seasonal = Array1::from_shape_fn(n, |t| indices[t % period]);
+    let remainder = match model {
+        DecompositionModel::Additive => &(&data - &trend) - &seasonal,
+        DecompositionModel::Multiplicative => &(&data / &trend) / &seasonal,
+    };
+
+    Ok(Decomposition { trend, seasonal, remainder, model })
+}
+
+/// Seasonal-trend decomposition using LOESS (Cleveland et al., 1990).
+///
+/// Always additive; take logs first (see `ML::transform`) for multiplicative
+/// seasonality. All windows are in samples and rounded up to odd numbers.
+pub struct Stl {
+    pub period: usize,
+    pub seasonal_window: usize,  // Smoothing of each cycle-subseries, larger means a more stable season
+    pub trend_window: Option<usize>,  // Defaults to the smallest odd >= 1.5 * period / (1 - 1.5 / seasonal_window)
+    pub low_pass_window: Option<usize>,  // Defaults to the smallest odd >= period
+    pub robust: bool,  // Downweight outliers with bisquare weights
+    pub inner_iterations: usize,
+    pub outer_iterations: usize,  // Only used when robust
+}
+
+impl Stl {
+    /// Create an STL decomposition with the usual defaults: seasonal window 7,
+    /// non-robust, two inner iterations.
+    pub fn new(period: usize) -> Self {
+        Stl {
+            period,
+            seasonal_window: 7,
+            trend_window: None,
+            low_pass_window: None,
+            robust: false,
+            inner_iterations: 2,
+            outer_iterations: 15,
+        }
+    }
+
+    /// Robust STL with one inner and fifteen outer iterations
+    pub fn robust(period: usize) -> Self {
+        Stl {
+            robust: true,
+            inner_iterations: 1,
+            ..Stl::new(period)
+        }
+    }
+
+    /// Decompose a series.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The series, as an `Array1`, a slice or a `Vec`
+    ///
+    /// # Returns
+    ///
+    /// Additive trend, seasonal and remainder components
+    pub fn fit<'a>(&self, data: impl Into<ArrayView1<'a, f64>>) -> Result<Decomposition, String> {
+        let data = data.into();
+        let n = data.len();
+        let period = self.period;
+        if period < 2 {
+            return Err("Seasonal period must be at least 2".to_string());
+        }
+        if n < 2 * period {
+            return Err("At least two full periods are needed for STL".to_string());
+        }
+        if self.seasonal_window < 3 {
+            return Err("Seasonal window must be at least 3".to_string());
+        }
+
+        let seasonal_window = odd(self.seasonal_window);
+        let trend_window = odd(self.trend_window.unwrap_or_else(|| {
+            (1.5 * period as f64 / (1.0 - 1.5 / seasonal_window as f64)).ceil() as usize
+        }));
+        let low_pass_window = odd(self.low_pass_window.unwrap_or(period));
+
+        let mut trend = vec![0.0; n];
+        let mut seasonal = vec![0.0; n];
+        let mut robustness = vec![1.0; n];
+        let outer = if self.robust { self.outer_iterations.max(1) } else { 1 };
+
+        for outer_step in 0..outer {
+            for _ in 0..self.inner_iterations.max(1) {
+                // 1-2. Detrend and smooth every cycle-subseries, extended one period each side
+                let detrended: Vec<f64> = (0..n).map(|t| data[t] - trend[t]).collect();
+                let mut cycle = vec![0.0; n + 2 * period];
+                for position in 0..period {
+                    let indices: Vec<usize> = (position..n).step_by(period).collect();
+                    let values: Vec<f64> = indices.iter().map(|&t| detrended[t]).collect();
+                    let weights: Vec<f64> = indices.iter().map(|&t| robustness[t]).collect();
+                    let m = values.len();
+                    for k in 0..m + 2 {
+                        let x = k as f64 - 1.0;
+                        let smoothed = loess(&values, &weights, seasonal_window, x).unwrap_or_else(|| {
+                            values[(k.saturating_sub(1)).min(m - 1)]
+                        });
+                        cycle[position + k * period] = smoothed;
+                    }
+                }
+
+                // 3. Low-pass filter of the cycle-subseries
+                let low_pass = moving_average(&moving_average(&moving_average(&cycle, period), period), 3);
+                let unit_weights = vec![1.0; n];
+                let low_pass: Vec<f64> = (0..n)
+                    .map(|t| loess(&low_pass, &unit_weights, low_pass_window, t as f64).unwrap_or(low_pass[t]))
+                    .collect();
+
+                // 4-6. Seasonal component, deseasonalise and smooth the trend
+                for t in 0..n {
+                    seasonal[t] = cycle[t + period] - low_pass[t];
+                }
+                let deseasonalised: Vec<f64> = (0..n).map(|t| data[t] - seasonal[t]).collect();
+                trend = (0..n)
+                    .map(|t| {
+                        loess(&deseasonalised, &robustness, trend_window, t as f64).unwrap_or(deseasonalised[t])
+                    })
+                    .collect();
+            }
+
+            if self.robust && outer_step + 1 < outer {
+                let residuals: Vec<f64> = (0..n).map(|t| (data[t] - trend[t] - seasonal[t]).abs()).collect();
+                robustness = bisquare_weights(&residuals);
+            }
+        }
+
+        let trend = Array1::from(trend);
+        let seasonal = Array1::from(seasonal);
+        let remainder = &(&data - &trend) - &seasonal;
+
+        Ok(Decomposition {
+            trend,
+            seasonal,
+            remainder,
+            model: DecompositionModel::Additive,
+        })
+    }
+}
+
+/// Smallest odd number not below `value`
+fn odd(value: usize) -> usize {
+    if value % 2 == 0 { value + 1 } else { value }
+}
+
+/// Moving average of length `window`; the output is `window - 1` shorter
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let mut result = Vec::with_capacity(values.len() + 1 - window);
+    let mut sum: f64 = values[..window].iter().sum();
+    result.push(sum / window as f64);
+    for t in window..values.len() {
+        sum += values[t] - values[t - window];
+        result.push(sum / window as f64);
+    }
+    result
+}
+
+/// Local linear regression at `x` over the `q` nearest of the points `0..values.len()`,
+/// weighted by a tricube kernel times `weights`. `None` when every weight is zero.
+fn loess(values: &[f64], weights: &[f64], q: usize, x: f64) -> Option<f64> {
+    let n = values.len();
+    let (left, right, bandwidth) = if q >= n {
+        let last = (n - 1) as f64;
+        (0, n - 1, x.max(last - x) + (q - n) as f64 / 2.0)
+    } else {
+        let start = (x.round() as isize - (q / 2) as isize).clamp(0, (n - q) as isize) as usize;
+        let end = start + q - 1;
+        (start, end, (x - start as f64).max(end as f64 - x))
+    };
+    let bandwidth = bandwidth.max(0.5);
+
+    let mut local_weights = Vec::with_capacity(right - left + 1);
+    let mut total = 0.0;
+    for (i, &weight) in weights.iter().enumerate().take(right + 1).skip(left) {
+        let distance = (i as f64 - x).abs() / bandwidth;
+        let w = if distance < 1.0 { (1.0 - distance.powi(3)).powi(3) * weight } else { 0.0 };
+        local_weights.push(w);
+        total += w;
+    }
+    if total <= 0.0 {
+        return None;
+    }
+
+    let center: f64 = (left..=right).zip(&local_weights).map(|(i, w)| w * i as f64).sum::<f64>() / total;
+    let mean: f64 = (left..=right).zip(&local_weights).map(|(i, w)| w * values[i]).sum::<f64>() / total;
+    let spread: f64 = (left..=right).zip(&local_weights).map(|(i, w)| w * (i as f64 - center).powi(2)).sum();
+
+    if spread <= 1e-12 * total {
+        return Some(mean);
+    }
+    let slope: f64 = (left..=right)
+        .zip(&local_weights)
+        .map(|(i, w)| w * (i as f64 - center) * values[i])
+        .sum::<f64>()
+        / spread;
+
+    Some(mean + slope * (x - center))
+}
+
+/// Bisquare robustness weights from absolute residuals
+fn bisquare_weights(abs_residuals: &[f64]) -> Vec<f64> {
+    let mut sorted = abs_residuals.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = sorted.len();
+    let median = if n % 2 == 0 { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 } else { sorted[n / 2] };
+    let scale = 6.0 * median;
+
+    abs_residuals
+        .iter()
+        .map(|&r| {
+            if scale <= 0.0 {
+                1.0
+            } else {
+                let u = r / scale;
+                if u < 1.0 { (1.0 - u * u).powi(2) } else { 0.0 }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEASON: [f64; 6] = [3.0, 5.0, 1.0, -2.0, -4.0, -3.0];
+
+    fn seasonal_series(n: usize) -> Vec<f64> {
+        (0..n).map(|t| 20.0 + 0.2 * t as f64 + SEASON[t % 6]).collect()
+    }
+
+    #[test]
+    fn test_classical_additive_recovers_season() {
+        let data = seasonal_series(36);
+        let result = classical_decomposition(&data, 6, DecompositionModel::Additive).unwrap();
+
+        for t in 0..36 {
+            assert!((result.seasonal[t] - SEASON[t % 6]).abs() < 1e-10);
+        }
+        // Edges have no centred average
+        assert!(result.trend[0].is_nan() && result.trend[35].is_nan());
+        for t in 3..33 {
+            assert!((result.trend[t] - (20.0 + 0.2 * t as f64)).abs() < 1e-10);
+            assert!(result.remainder[t].abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_classical_multiplicative() {
+        let factors = [1.2, 0.8, 1.1, 0.9];
+        let data: Array1<f64> = Array1::from_shape_fn(24, |t| 50.0 * factors[t % 4]);
+        let result = classical_decomposition(&data, 4, DecompositionModel::Multiplicative).unwrap();
+
+        for t in 0..24 {
+            assert!((result.seasonal[t] - factors[t % 4]).abs() < 1e-10);
+        }
+        assert!((result.trend[10] - 50.0).abs() < 1e-10);
+        assert!((result.remainder[10] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_stl_separates_trend_and_season() {
+        let data = seasonal_series(72);
+        let result = Stl::new(6).fit(&data).unwrap();
+
+        // Components always add back up to the data
+        for (t, value) in data.iter().enumerate() {
+            let total = result.trend[t] + result.seasonal[t] + result.remainder[t];
+            assert!((total - value).abs() < 1e-10);
+        }
+        for t in 12..60 {
+            assert!((result.seasonal[t] - SEASON[t % 6]).abs() < 0.1, "t = {}", t);
+            assert!((result.trend[t] - (20.0 + 0.2 * t as f64)).abs() < 0.1, "t = {}", t);
+        }
+    }
+
+    #[test]
+    fn test_robust_stl_isolates_spike() {
+        let mut data = seasonal_series(72);
+        data[40] += 30.0;
+        let result = Stl::robust(6).fit(&data).unwrap();
+
+        // The spike ends up in the remainder instead of distorting the trend
+        assert!(result.remainder[40] > 25.0);
+        assert!((result.trend[40] - (20.0 + 0.2 * 40.0)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_short_series_rejected() {
+        let data = vec![1.0, 2.0, 3.0];
+        assert!(Stl::new(4).fit(&data).is_err());
+        assert!(classical_decomposition(&data, 4, DecompositionModel::Additive).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+// src/ml/diagnostics.rs
+use ndarray::Array1;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+/// Result of a goodness-of-fit test on a series of residuals
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p_value: f64,
+    pub df: f64,  // Degrees of freedom of the reference chi-square distribution
+}
+
+/// Sample autocorrelation function.
+///
+/// # Arguments
+///
+/// * `series` - The series to analyse
+/// * `max_lag` - Highest lag to compute (must be smaller than the series length)
+///
+/// # Returns
+///
+/// The autocorrelations for lags `0..=max_lag`, with lag 0 always equal to 1
+pub fn acf(series: &Array1<f64>, max_lag: usize) -> Result<Array1<f64>, String> {
+    let n = series.len();
+    if n < 2 {
+        return Err("At least two observations are needed for the ACF".to_string());
+    }
+    if max_lag >= n {
+        return Err("Maximum lag must be smaller than the series length".to_string());
+    }
+
+    let mean = series.sum() / n as f64;
+    let centered = series.mapv(|x| x - mean);
+    let denominator = centered.dot(&centered);
+    if denominator == 0.0 {
+        return Err("ACF is undefined for a constant series".to_string());
+    }
+
+    let mut result = Array1::zeros(max_lag + 1);
+    for k in 0..=max_lag {
+        let mut sum = 0.0;
+        for t in k..n {
+            sum += centered[t] * centered[t - k];
+        }
+        result[k] = sum / denominator;
+    }
+
+    Ok(result)
+}
+
+/// Sample partial autocorrelation function using the Durbin-Levinson recursion.
+///
+/// # Arguments
+///
+/// * `series` - The series to analyse
+/// * `max_lag` - Highest lag to compute (must be smaller than the series length)
+///
+/// # Returns
+///
+/// The partial autocorrelations for lags `0..=max_lag`, with lag 0 equal to 1
+pub fn pacf(series: &Array1<f64>, max_lag: usize) -> Result<Array1<f64>, String> {
+    let r = acf(series, max_lag)?;
+
+    let mut result = Array1::zeros(max_lag + 1);
+    result[0] = 1.0;
+
+    // phi[j] holds the AR(k) coefficients of the current recursion step
+    let mut phi: Vec<f64> = Vec::with_capacity(max_lag);
+    for k in 1..=max_lag {
+        let mut numerator = r[k];
+        let mut denominator = 1.0;
+        for j in 1..k {
+            numerator -= phi[j - 1] * r[k - j];
+            denominator -= phi[j - 1] * r[j];
+        }
+        let phi_kk = numerator / denominator;
+
+        let previous = phi.clone();
+        for j in 1..k {
+            phi[j - 1] = previous[j - 1] - phi_kk * previous[k - j - 1];
+        }
+        phi.push(phi_kk);
+
+        result[k] = phi_kk;
+    }
+
+    Ok(result)
+}
+
+/// Approximate 95% confidence bound for the ACF/PACF of white noise.
+///
+/// Autocorrelations outside `±bound` are significant at the 5% level, which
+/// is the usual rule of thumb for choosing `p` (from the PACF) and `q` (from the ACF).
+pub fn white_noise_bound(n: usize) -> f64 {
+    1.96 / (n as f64).sqrt()
+}
+
+/// Ljung-Box portmanteau test for autocorrelation in residuals.
+///
+/// # Arguments
+///
+/// * `residuals` - Model residuals
+/// * `lags` - Number of autocorrelations included in the statistic
+/// * `fitted_params` - Number of ARMA parameters estimated (`p + q`), removed from the degrees of freedom
+///
+/// # Returns
+///
+/// The Q statistic and its chi-square p-value; small p-values mean the
+/// residuals are still autocorrelated and the model is inadequate
+pub fn ljung_box(residuals: &Array1<f64>, lags: usize, fitted_params: usize) -> Result<TestResult, String> {
+    if lags == 0 {
+        return Err("Ljung-Box test needs at least one lag".to_string());
+    }
+    if lags <= fitted_params {
+        return Err("Number of lags must exceed the number of fitted parameters".to_string());
+    }
+
+    let n = residuals.len() as f64;
+    let r = acf(residuals, lags)?;
+
+    let mut statistic = 0.0;
+    for k in 1..=lags {
+        statistic += r[k] * r[k] / (n - k as f64);
+    }
+    statistic *= n * (n + 2.0);
+
+    let df = (lags - fitted_params) as f64;
+    let p_value = chi_square_sf(statistic, df)?;
+
+    Ok(TestResult { statistic, p_value, df })
+}
+
+/// Jarque-Bera test for normality of residuals.
+///
+/// # Arguments
+///
+/// * `residuals` - Model residuals
+///
+/// # Returns
+///
+/// The JB statistic and its chi-square (2 df) p-value; small p-values mean
+/// the residuals are not normally distributed
+pub fn jarque_bera(residuals: &Array1<f64>) -> Result<TestResult, String> {
+    let n = residuals.len();
+    if n < 3 {
+        return Err("At least three observations are needed for the Jarque-Bera test".to_string());
+    }
+
+    let mean = residuals.sum() / n as f64;
+    let moment = |order: i32| residuals.iter().map(|&x| (x - mean).powi(order)).sum::<f64>() / n as f64;
+    let m2 = moment(2);
+    if m2 == 0.0 {
+        return Err("Jarque-Bera test is undefined for constant residuals".to_string());
+    }
+
+    let skewness = moment(3) / m2.powf(1.5);
+    let kurtosis = moment(4) / (m2 * m2);
+    let statistic = n as f64 / 6.0 * (skewness * skewness + (kurtosis - 3.0).powi(2) / 4.0);
+
+    let df = 2.0;
+    let p_value = chi_square_sf(statistic, df)?;
+
+    Ok(TestResult { statistic, p_value, df })
+}
+
+/// Upper tail probability of a chi-square distribution.
+fn chi_square_sf(statistic: f64, df: f64) -> Result<f64, String> {
+    let distribution = ChiSquared::new(df).map_err(|e| e.to_string())?;
+    Ok(distribution.sf(statistic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acf() {
+        let series = Array1::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let result = acf(&series, 2).unwrap();
+        assert_eq!(result[0], 1.0);
+        assert!((result[1] - 0.4).abs() < 1e-12);
+        assert!((result[2] + 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_acf_invalid_lag() {
+        let series = Array1::from(vec![1.0, 2.0, 3.0]);
+        assert!(acf(&series, 3).is_err());
+    }
+
+    #[test]
+    fn test_pacf_first_two_lags() {
+        let series = Array1::from(vec![2.0, 4.0, 3.0, 6.0, 5.0, 7.0, 6.0, 9.0, 8.0, 10.0]);
+        let r = acf(&series, 2).unwrap();
+        let result = pacf(&series, 2).unwrap();
+
+        // Lag 1 PACF equals the ACF; lag 2 has a closed form
+        assert!((result[1] - r[1]).abs() < 1e-12);
+        let expected = (r[2] - r[1] * r[1]) / (1.0 - r[1] * r[1]);
+        assert!((result[2] - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ljung_box() {
+        let residuals = Array1::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let result = ljung_box(&residuals, 2, 0).unwrap();
+        // Q = 5 * 7 * (0.4^2 / 4 + 0.1^2 / 3)
+        let expected = 35.0 * (0.16 / 4.0 + 0.01 / 3.0);
+        assert!((result.statistic - expected).abs() < 1e-12);
+        assert_eq!(result.df, 2.0);
+        // Chi-square with 2 df has survival function exp(-x / 2)
+        assert!((result.p_value - (-expected / 2.0).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jarque_bera_symmetric_sample() {
+        let residuals = Array1::from(vec![-2.0, -1.0, 0.0, 1.0, 2.0]);
+        let result = jarque_bera(&residuals).unwrap();
+        // Skewness is 0 and kurtosis is 1.7 for this sample
+        let expected = 5.0 / 6.0 * (1.3f64 * 1.3 / 4.0);
+        assert!((result.statistic - expected).abs() < 1e-12);
+    }
+}
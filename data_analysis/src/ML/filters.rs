@@ -0,0 +1,653 @@
+// src/ml/filters.rs
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use ndarray::{Array1, Array2};
+use ndarray_linalg::Inverse;
+
+use crate::ML::rolling::{Alignment, OrderStatistics, Rolling, WindowState};
+
+/// Common interface of the filters that run one sample at a time.
+///
+/// Every output only depends on the current and past samples, so streaming
+/// filters delay the signal; use the batch functions for centred or
+/// zero-phase results.
+///
+/// NaN and infinite samples, e.g. dropped sensor readings, are skipped: they
+/// leave the state untouched and the filter repeats its last output, which
+/// is NaN until the first finite sample.
+pub trait StreamingFilter {
+    /// Feed the next sample and return the filtered value
+    fn update(&mut self, value: f64) -> f64;
+
+    /// Forget every sample seen so far
+    fn reset(&mut self);
+
+    /// Run the filter over a whole series from a fresh state
+    fn apply(&mut self, data: &Array1<f64>) -> Array1<f64> {
+        self.reset();
+        data.iter().map(|&v| self.update(v)).collect()
+    }
+}
+
+fn check_window(window: usize, n: usize) -> Result<(), String> {
+    if window == 0 || window % 2 == 0 {
+        return Err("Window must be odd".to_string());
+    }
+    if window > n {
+        return Err("Window is longer than the data".to_string());
+    }
+    Ok(())
+}
+
+/// Centred moving average.
+///
+/// Near the ends the window shrinks to the samples available. The input must
+/// be finite, since a single NaN would spread through the running sums.
+///
+/// # Arguments
+///
+/// * `data` - The signal
+/// * `window` - Odd number of samples averaged
+///
+/// # Returns
+///
+/// The smoothed signal, same length as the input
+pub fn moving_average(data: &Array1<f64>, window: usize) -> Result<Array1<f64>, String> {
+    check_window(window, data.len())?;
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err("Data contains NaN or infinite values, impute the gaps first".to_string());
+    }
+    let half = window / 2;
+    let mut cumulative = vec![0.0; data.len() + 1];
+    for (i, v) in data.iter().enumerate() {
+        cumulative[i + 1] = cumulative[i] + v;
+    }
+    Ok((0..data.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half + 1).min(data.len());
+            (cumulative[end] - cumulative[start]) / (end - start) as f64
+        })
+        .collect())
+}
+
+/// Centred running median, robust to isolated spikes.
+///
+/// Near the ends the window shrinks to the samples available.
+///
+/// # Arguments
+///
+/// * `data` - The signal
+/// * `window` - Odd number of samples in each median
+///
+/// # Returns
+///
+/// The filtered signal, same length as the input
+pub fn median_filter(data: &Array1<f64>, window: usize) -> Result<Array1<f64>, String> {
+    check_window(window, data.len())?;
+    let half = window / 2;
+    let values = data.to_vec();
+    let mut rolling = Rolling::new(window);
+    rolling.alignment = Alignment::Centered;
+    let interior = rolling.median(&values)?;
+
+    let edge_median = |start: usize, end: usize| {
+        let mut segment = values[start..end].to_vec();
+        segment.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let m = segment.len();
+        if m % 2 == 0 { (segment[m / 2 - 1] + segment[m / 2]) / 2.0 } else { segment[m / 2] }
+    };
+    let mut result = Array1::<f64>::zeros(values.len());
+    for (&i, &v) in interior.index.iter().zip(interior.values.iter()) {
+        result[i] = v;
+    }
+    for i in 0..half {
+        result[i] = edge_median(0, i + half + 1);
+        let j = values.len() - 1 - i;
+        result[j] = edge_median(j - half, values.len());
+    }
+    Ok(result)
+}
+
+/// Weights evaluating a least-squares polynomial fitted over `window`
+/// samples. Row `r` gives the fitted value at position `r` of the window.
+fn savitzky_golay_weights(window: usize, order: usize) -> Result<Array2<f64>, String> {
+    let half = (window / 2) as f64;
+    // Positions scaled to [-1, 1] keep the normal equations well conditioned
+    let position = |i: usize| if half > 0.0 { (i as f64 - half) / half } else { 0.0 };
+    let mut design = Array2::<f64>::zeros((window, order + 1));
+    for i in 0..window {
+        for p in 0..=order {
+            design[[i, p]] = position(i).powi(p as i32);
+        }
+    }
+    let normal_inv = design
+        .t()
+        .dot(&design)
+        .inv()
+        .map_err(|_| "Savitzky-Golay fit is singular".to_string())?;
+    let projection = normal_inv.dot(&design.t());
+    Ok(design.dot(&projection))
+}
+
+/// Savitzky-Golay smoothing: a polynomial of degree `order` is fitted by
+/// least squares around every sample. Peaks and slopes are preserved far
+/// better than with a moving average of the same length.
+///
+/// Near the ends the polynomial fitted to the first or last full window is used.
+///
+/// # Arguments
+///
+/// * `data` - The signal
+/// * `window` - Odd number of samples in each fit
+/// * `order` - Polynomial degree, smaller than `window`
+///
+/// # Returns
+///
+/// The smoothed signal, same length as the input
+pub fn savitzky_golay(data: &Array1<f64>, window: usize, order: usize) -> Result<Array1<f64>, String> {
+    check_window(window, data.len())?;
+    if order >= window {
+        return Err("Polynomial order must be smaller than the window".to_string());
+    }
+    let weights = savitzky_golay_weights(window, order)?;
+    let half = window / 2;
+    let n = data.len();
+    Ok((0..n)
+        .map(|i| {
+            let start = i.saturating_sub(half).min(n - window);
+            let row = weights.row(i - start);
+            (0..window).map(|k| row[k] * data[start + k]).sum()
+        })
+        .collect())
+}
+
+/// Trailing moving average, averaging the samples seen so far until the window fills
+#[derive(Debug, Clone)]
+pub struct MovingAverage {
+    window: usize,
+    buffer: VecDeque<f64>,
+    sum: f64,
+}
+
+impl MovingAverage {
+    pub fn new(window: usize) -> Result<Self, String> {
+        if window == 0 {
+            return Err("Window must be at least 1".to_string());
+        }
+        Ok(MovingAverage {
+            window,
+            buffer: VecDeque::with_capacity(window),
+            sum: 0.0,
+        })
+    }
+}
+
+impl StreamingFilter for MovingAverage {
+    fn update(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            // 0 / 0 is NaN while the buffer is empty
+            return self.sum / self.buffer.len() as f64;
+        }
+        self.buffer.push_back(value);
+        self.sum += value;
+        if self.buffer.len() > self.window {
+            self.sum -= self.buffer.pop_front().unwrap();
+        }
+        self.sum / self.buffer.len() as f64
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.sum = 0.0;
+    }
+}
+
+/// Trailing running median, over the samples seen so far until the window fills
+#[derive(Debug, Clone)]
+pub struct MovingMedian {
+    window: usize,
+    buffer: VecDeque<f64>,
+    order: OrderStatistics,  // The buffered samples, sorted
+    count: usize,  // Samples seen, their ids in `order`
+}
+
+impl MovingMedian {
+    pub fn new(window: usize) -> Result<Self, String> {
+        if window == 0 {
+            return Err("Window must be at least 1".to_string());
+        }
+        Ok(MovingMedian {
+            window,
+            buffer: VecDeque::with_capacity(window),
+            order: OrderStatistics::new(),
+            count: 0,
+        })
+    }
+}
+
+impl StreamingFilter for MovingMedian {
+    fn update(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return if self.buffer.is_empty() { f64::NAN } else { self.order.quantile(0.5) };
+        }
+        self.order.push(self.count, value);
+        self.buffer.push_back(value);
+        self.count += 1;
+        if self.buffer.len() > self.window {
+            let oldest = self.buffer.pop_front().unwrap();
+            self.order.pop(self.count - self.window - 1, oldest);
+        }
+        self.order.quantile(0.5)
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.order = OrderStatistics::new();
+        self.count = 0;
+    }
+}
+
+/// Causal Savitzky-Golay filter: the polynomial fitted to the last `window`
+/// samples is evaluated at the newest one. Samples pass through unchanged
+/// until the window fills.
+#[derive(Debug, Clone)]
+pub struct SavitzkyGolay {
+    weights: Array1<f64>,
+    buffer: VecDeque<f64>,
+}
+
+impl SavitzkyGolay {
+    pub fn new(window: usize, order: usize) -> Result<Self, String> {
+        if window == 0 || order >= window {
+            return Err("Polynomial order must be smaller than the window".to_string());
+        }
+        let weights = savitzky_golay_weights(window, order)?;
+        Ok(SavitzkyGolay {
+            weights: weights.row(window - 1).to_owned(),
+            buffer: VecDeque::with_capacity(window),
+        })
+    }
+}
+
+impl StreamingFilter for SavitzkyGolay {
+    fn update(&mut self, value: f64) -> f64 {
+        if value.is_finite() {
+            self.buffer.push_back(value);
+            if self.buffer.len() > self.weights.len() {
+                self.buffer.pop_front();
+            }
+        }
+        if self.buffer.len() < self.weights.len() {
+            return self.buffer.back().copied().unwrap_or(f64::NAN);
+        }
+        self.buffer.iter().zip(self.weights.iter()).map(|(x, w)| x * w).sum()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// Second-order IIR section in transposed direct form II
+#[derive(Debug, Clone, PartialEq)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],  // a1 and a2, a0 is normalised to 1
+    state: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Biquad { b, a, state: [0.0; 2] }
+    }
+
+    fn gain_at_dc(&self) -> f64 {
+        (self.b[0] + self.b[1] + self.b[2]) / (1.0 + self.a[0] + self.a[1])
+    }
+
+    /// Put the section in the state it reaches after a long constant input
+    fn settle(&mut self, input: f64) {
+        let output = self.gain_at_dc() * input;
+        self.state[1] = self.b[2] * input - self.a[1] * output;
+        self.state[0] = self.b[1] * input - self.a[0] * output + self.state[1];
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.state[0];
+        self.state[0] = self.b[1] * input - self.a[0] * output + self.state[1];
+        self.state[1] = self.b[2] * input - self.a[1] * output;
+        output
+    }
+}
+
+/// Butterworth IIR filter: maximally flat pass band, built as a cascade of
+/// second-order sections for numerical stability.
+///
+/// As a `StreamingFilter` the state is settled on the first sample, so a
+/// sensor that starts at 25 °C does not ramp up from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Butterworth {
+    sections: Vec<Biquad>,
+    last: Option<f64>,  // Latest output, None until the first sample settles the state
+}
+
+/// Which side of the cutoff a Butterworth section keeps
+#[derive(Clone, Copy)]
+enum Pass {
+    Low,
+    High,
+}
+
+impl Butterworth {
+    /// Sections of a low- or high-pass design, via the bilinear transform
+    /// with the cutoff pre-warped
+    fn sections(order: usize, cutoff: f64, sample_rate: f64, pass: Pass) -> Result<Vec<Biquad>, String> {
+        if order == 0 {
+            return Err("Order must be at least 1".to_string());
+        }
+        if !(cutoff > 0.0 && cutoff < sample_rate / 2.0) {
+            return Err("Cutoff must lie between 0 and the Nyquist frequency".to_string());
+        }
+        let k = (PI * cutoff / sample_rate).tan();
+        let mut sections = Vec::new();
+        for i in 0..order / 2 {
+            // Angle of the analog pole pair from the negative real axis
+            let angle = PI * (order - 2 * i - 1) as f64 / (2 * order) as f64;
+            let q = 1.0 / (2.0 * angle.cos());
+            let norm = 1.0 / (1.0 + k / q + k * k);
+            let a = [2.0 * (k * k - 1.0) * norm, (1.0 - k / q + k * k) * norm];
+            let b = match pass {
+                Pass::Low => {
+                    let b0 = k * k * norm;
+                    [b0, 2.0 * b0, b0]
+                }
+                Pass::High => [norm, -2.0 * norm, norm],
+            };
+            sections.push(Biquad::new(b, a));
+        }
+        if order % 2 == 1 {
+            let norm = 1.0 / (1.0 + k);
+            let a = [(k - 1.0) * norm, 0.0];
+            let b = match pass {
+                Pass::Low => [k * norm, k * norm, 0.0],
+                Pass::High => [norm, -norm, 0.0],
+            };
+            sections.push(Biquad::new(b, a));
+        }
+        Ok(sections)
+    }
+
+    /// # Arguments
+    ///
+    /// * `order` - Filter order, the roll-off is 6 dB per octave per order
+    /// * `cutoff` - Frequency where the gain drops by 3 dB, in Hz
+    /// * `sample_rate` - Samples per second
+    pub fn low_pass(order: usize, cutoff: f64, sample_rate: f64) -> Result<Self, String> {
+        Ok(Butterworth {
+            sections: Self::sections(order, cutoff, sample_rate, Pass::Low)?,
+            last: None,
+        })
+    }
+
+    pub fn high_pass(order: usize, cutoff: f64, sample_rate: f64) -> Result<Self, String> {
+        Ok(Butterworth {
+            sections: Self::sections(order, cutoff, sample_rate, Pass::High)?,
+            last: None,
+        })
+    }
+
+    /// Band-pass built as a high-pass at `low` followed by a low-pass at
+    /// `high`, both of the given order
+    pub fn band_pass(order: usize, low: f64, high: f64, sample_rate: f64) -> Result<Self, String> {
+        if low >= high {
+            return Err("Lower cutoff must be below the upper cutoff".to_string());
+        }
+        let mut sections = Self::sections(order, low, sample_rate, Pass::High)?;
+        sections.extend(Self::sections(order, high, sample_rate, Pass::Low)?);
+        Ok(Butterworth { sections, last: None })
+    }
+
+    /// Settle every section as if `input` had been applied forever
+    fn settle(&mut self, input: f64) {
+        let mut level = input;
+        for section in self.sections.iter_mut() {
+            section.settle(level);
+            level *= section.gain_at_dc();
+        }
+    }
+
+    /// Causal filtering of a whole series, starting settled on the first sample
+    pub fn filter(&self, data: &Array1<f64>) -> Array1<f64> {
+        self.clone().apply(data)
+    }
+
+    /// Zero-phase filtering: the signal is filtered forward and then
+    /// backward, which cancels the phase delay and squares the magnitude
+    /// response. The ends are padded with an odd reflection to limit
+    /// transients, though sharp or high-pass designs can still ring over the
+    /// first and last few cutoff periods.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The signal, at least two samples
+    ///
+    /// # Returns
+    ///
+    /// The filtered signal, aligned with the input
+    pub fn filtfilt(&self, data: &Array1<f64>) -> Result<Array1<f64>, String> {
+        let n = data.len();
+        if n < 2 {
+            return Err("Zero-phase filtering needs at least two samples".to_string());
+        }
+        let pad = (3 * (2 * self.sections.len() + 1)).min(n - 1);
+        let mut extended = Vec::with_capacity(n + 2 * pad);
+        extended.extend((1..=pad).rev().map(|i| 2.0 * data[0] - data[i]));
+        extended.extend(data.iter());
+        extended.extend((1..=pad).map(|i| 2.0 * data[n - 1] - data[n - 1 - i]));
+
+        let mut forward = self.clone();
+        forward.reset();
+        let mut pass: Vec<f64> = extended.iter().map(|&v| forward.update(v)).collect();
+        pass.reverse();
+        let mut backward = self.clone();
+        backward.reset();
+        let mut pass: Vec<f64> = pass.iter().map(|&v| backward.update(v)).collect();
+        pass.reverse();
+        Ok(Array1::from(pass[pad..pad + n].to_vec()))
+    }
+}
+
+impl StreamingFilter for Butterworth {
+    fn update(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return self.last.unwrap_or(f64::NAN);
+        }
+        if self.last.is_none() {
+            self.settle(value);
+        }
+        let output = self.sections.iter_mut().fold(value, |x, section| section.process(x));
+        self.last = Some(output);
+        output
+    }
+
+    fn reset(&mut self) {
+        for section in self.sections.iter_mut() {
+            section.state = [0.0; 2];
+        }
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::uniform_noise;
+
+    fn thermistor(n: usize, sample_rate: f64) -> (Array1<f64>, Array1<f64>) {
+        // Slow temperature drift with uniform noise
+        let clean: Array1<f64> = (0..n)
+            .map(|i| 25.0 + 2.0 * (2.0 * PI * 0.05 * i as f64 / sample_rate).sin())
+            .collect();
+        let noisy = &clean + &Array1::from(uniform_noise(n, 7));
+        (clean, noisy)
+    }
+
+    fn rmse(a: &Array1<f64>, b: &Array1<f64>) -> f64 {
+        ((a - b).mapv(|v| v * v).sum() / a.len() as f64).sqrt()
+    }
+
+    fn tone(n: usize, frequency: f64, sample_rate: f64) -> Array1<f64> {
+        (0..n).map(|i| (2.0 * PI * frequency * i as f64 / sample_rate).sin()).collect()
+    }
+
+    #[test]
+    fn test_moving_average_and_median() {
+        let ramp: Array1<f64> = (0..20).map(|i| i as f64).collect();
+        let smoothed = moving_average(&ramp, 5).unwrap();
+        for i in 2..18 {
+            assert!((smoothed[i] - ramp[i]).abs() < 1e-12);
+        }
+        assert_eq!(smoothed[0], 1.0);
+
+        let mut spiky = Array1::from_elem(15, 20.0);
+        spiky[7] = 90.0;
+        spiky[0] = -5.0;
+        let cleaned = median_filter(&spiky, 3).unwrap();
+        assert!(cleaned.iter().skip(1).all(|&v| v == 20.0));
+        assert_eq!(cleaned[0], 7.5);
+
+        assert!(moving_average(&ramp, 4).is_err());
+        let mut gap = ramp.clone();
+        gap[3] = f64::NAN;
+        assert!(moving_average(&gap, 5).is_err());
+        assert!(median_filter(&ramp, 21).is_err());
+    }
+
+    #[test]
+    fn test_savitzky_golay() {
+        // A cubic is reproduced exactly, ends included
+        let cubic: Array1<f64> = (0..30).map(|i| {
+            let t = i as f64 / 10.0;
+            t * t * t - 2.0 * t + 1.0
+        }).collect();
+        let smoothed = savitzky_golay(&cubic, 7, 3).unwrap();
+        assert!(rmse(&smoothed, &cubic) < 1e-9);
+
+        let (clean, noisy) = thermistor(500, 10.0);
+        let smoothed = savitzky_golay(&noisy, 21, 2).unwrap();
+        assert!(rmse(&smoothed, &clean) < 0.5 * rmse(&noisy, &clean));
+
+        let mut streaming = SavitzkyGolay::new(7, 3).unwrap();
+        let causal = streaming.apply(&cubic);
+        assert!((causal[29] - cubic[29]).abs() < 1e-9);
+        assert_eq!(causal[3], cubic[3]);
+        assert!(savitzky_golay(&cubic, 5, 5).is_err());
+    }
+
+    #[test]
+    fn test_butterworth_low_pass() {
+        let sample_rate = 10.0;
+        let filter = Butterworth::low_pass(4, 0.5, sample_rate).unwrap();
+
+        // Settled on the first sample, a constant passes untouched
+        let constant = Array1::from_elem(50, 25.0);
+        assert!(filter.filter(&constant).iter().all(|v| (v - 25.0).abs() < 1e-9));
+
+        let slow = tone(2000, 0.1, sample_rate);
+        let fast = tone(2000, 3.0, sample_rate);
+        let slow_out = filter.filtfilt(&slow).unwrap();
+        let fast_out = filter.filtfilt(&fast).unwrap();
+        // Zero phase: the pass band comes out in place, without the causal delay
+        assert!(rmse(&slow_out, &slow) < 0.01);
+        assert!(rmse(&filter.filter(&slow), &slow) > 0.05);
+        assert!(fast_out.iter().skip(100).take(1800).all(|v| v.abs() < 1e-3));
+
+        let (clean, noisy) = thermistor(1000, sample_rate);
+        assert!(rmse(&filter.filtfilt(&noisy).unwrap(), &clean) < 0.5 * rmse(&noisy, &clean));
+
+        // Gain at the cutoff is -3 dB, squared by the two passes
+        let at_cutoff = filter.filtfilt(&tone(4000, 0.5, sample_rate)).unwrap();
+        let amplitude = at_cutoff.iter().skip(1000).take(2000).fold(0.0_f64, |m, v| m.max(v.abs()));
+        assert!((amplitude - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_butterworth_high_and_band_pass() {
+        let sample_rate = 100.0;
+        let high = Butterworth::high_pass(3, 5.0, sample_rate).unwrap();
+        let offset = tone(1000, 20.0, sample_rate).mapv(|v| v + 30.0);
+        let out = high.filtfilt(&offset).unwrap();
+        let core = |a: &Array1<f64>| a.slice(ndarray::s![100..900]).to_owned();
+        assert!(rmse(&core(&out), &core(&tone(1000, 20.0, sample_rate))) < 0.01);
+
+        let band = Butterworth::band_pass(4, 5.0, 15.0, sample_rate).unwrap();
+        let mixed = &tone(2000, 1.0, sample_rate) + &tone(2000, 10.0, sample_rate) + &tone(2000, 40.0, sample_rate);
+        let out = band.filtfilt(&mixed).unwrap();
+        let middle = tone(2000, 10.0, sample_rate);
+        let out = out.slice(ndarray::s![100..1900]).to_owned();
+        assert!(rmse(&out, &middle.slice(ndarray::s![100..1900]).to_owned()) < 0.05);
+
+        assert!(Butterworth::low_pass(2, 60.0, sample_rate).is_err());
+        assert!(Butterworth::band_pass(2, 15.0, 5.0, sample_rate).is_err());
+    }
+
+    #[test]
+    fn test_streaming_matches_batch() {
+        let (_, noisy) = thermistor(300, 10.0);
+        let mut low = Butterworth::low_pass(2, 1.0, 10.0).unwrap();
+        let batch = low.filter(&noisy);
+        let streamed: Array1<f64> = noisy.iter().map(|&v| low.update(v)).collect();
+        assert_eq!(batch, streamed);
+        // Zero-phase filtering ignores the state left by streaming
+        let fresh = Butterworth::low_pass(2, 1.0, 10.0).unwrap();
+        assert_eq!(low.filtfilt(&noisy).unwrap(), fresh.filtfilt(&noisy).unwrap());
+
+        let mut average = MovingAverage::new(5).unwrap();
+        let out = average.apply(&noisy);
+        let expected = noisy.slice(ndarray::s![10..15]).sum() / 5.0;
+        assert!((out[14] - expected).abs() < 1e-12);
+        assert_eq!(out[0], noisy[0]);
+
+        let mut median = MovingMedian::new(3).unwrap();
+        let out = median.apply(&Array1::from(vec![1.0, 9.0, 2.0, 3.0]));
+        assert_eq!(out.to_vec(), vec![1.0, 5.0, 2.0, 3.0]);
+
+        let mut median = MovingMedian::new(9).unwrap();
+        let out = median.apply(&noisy);
+        let mut last: Vec<f64> = noisy.slice(ndarray::s![291..]).to_vec();
+        last.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(out[299], last[4]);
+    }
+
+    #[test]
+    fn test_streaming_skips_non_finite_samples() {
+        let (_, noisy) = thermistor(100, 10.0);
+        let mut gappy = noisy.clone();
+        gappy[0] = f64::NAN;
+        gappy[40] = f64::NAN;
+        gappy[41] = f64::INFINITY;
+        gappy[70] = f64::NEG_INFINITY;
+        let kept: Array1<f64> = gappy.iter().copied().filter(|v| v.is_finite()).collect();
+
+        let filters: Vec<Box<dyn StreamingFilter>> = vec![
+            Box::new(MovingAverage::new(5).unwrap()),
+            Box::new(MovingMedian::new(5).unwrap()),
+            Box::new(SavitzkyGolay::new(7, 2).unwrap()),
+            Box::new(Butterworth::low_pass(2, 1.0, 10.0).unwrap()),
+        ];
+        for mut filter in filters {
+            let out = filter.apply(&gappy);
+            // Same outputs as without the gaps, each gap repeating the one before it
+            let expected = filter.apply(&kept);
+            let finite: Vec<f64> = out.iter().zip(&gappy).filter(|(_, v)| v.is_finite()).map(|(o, _)| *o).collect();
+            assert_eq!(finite, expected.to_vec());
+            assert!(out[0].is_nan());
+            assert_eq!(out[40], out[39]);
+            assert_eq!(out[41], out[39]);
+            assert_eq!(out[70], out[69]);
+            assert!(out.iter().skip(1).all(|v| v.is_finite()));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// src/ml/forecaster.rs
+use ndarray::Array1;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::ML::arima::ARIMA;
+use crate::ML::kalman::{LocalLevel, LocalLinearTrend};
+use crate::ML::smoothing::{Holt, HoltWinters, Seasonality, SimpleExponentialSmoothing};
+
+/// Common interface for time-series models that can be fitted and then
+/// forecast past the end of their training series.
+pub trait Forecaster {
+    /// Fit the model to a series, replacing any previous fit
+    fn fit(&mut self, data: &Array1<f64>) -> Result<(), String>;
+
+    /// Forecast `horizon` steps past the end of the series given to `fit`
+    fn predict(&self, horizon: usize) -> Result<Array1<f64>, String>;
+
+    /// One-step-ahead in-sample predictions.
+    ///
+    /// Models need a few observations before their first prediction, so the
+    /// result covers the last `len()` observations of the training series.
+    fn fitted_values(&self) -> Result<Array1<f64>, String>;
+
+    /// One-step-ahead in-sample errors, aligned with `fitted_values`
+    fn residuals(&self) -> Result<Array1<f64>, String>;
+
+    /// Variance of the 1..=`horizon` step forecast errors.
+    ///
+    /// Models without an error model keep this default and do not offer intervals.
+    fn forecast_variance(&self, _horizon: usize) -> Result<Array1<f64>, String> {
+        Err("Forecast variance is not available for this model".to_string())
+    }
+
+    /// Forecasts with symmetric normal prediction intervals at `level` (e.g. 0.95)
+    fn predict_interval(&self, horizon: usize, level: f64) -> Result<ForecastInterval, String> {
+        if !(level > 0.0 && level < 1.0) {
+            return Err("Interval level must be in (0, 1)".to_string());
+        }
+        let mean = self.predict(horizon)?;
+        let variance = self.forecast_variance(horizon)?;
+
+        let normal = Normal::new(0.0, 1.0).map_err(|e| e.to_string())?;
+        let z = normal.inverse_cdf(0.5 + level / 2.0);
+        let half_width = variance.mapv(|v| z * v.sqrt());
+
+        Ok(ForecastInterval {
+            lower: &mean - &half_width,
+            upper: &mean + &half_width,
+            mean,
+            level,
+        })
+    }
+}
+
+/// Boxed models, such as those from `ModelConfig::build`, forward to the
+/// model inside so they can be wrapped like any other forecaster.
+impl<F: Forecaster + ?Sized> Forecaster for Box<F> {
+    fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+        (**self).fit(data)
+    }
+
+    fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        (**self).predict(horizon)
+    }
+
+    fn fitted_values(&self) -> Result<Array1<f64>, String> {
+        (**self).fitted_values()
+    }
+
+    fn residuals(&self) -> Result<Array1<f64>, String> {
+        (**self).residuals()
+    }
+
+    fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        (**self).forecast_variance(horizon)
+    }
+
+    fn predict_interval(&self, horizon: usize, level: f64) -> Result<ForecastInterval, String> {
+        (**self).predict_interval(horizon, level)
+    }
+}
+
+/// Point forecasts with lower and upper prediction bounds
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastInterval {
+    pub mean: Array1<f64>,
+    pub lower: Array1<f64>,
+    pub upper: Array1<f64>,
+    pub level: f64,
+}
+
+/// Model choice and hyperparameters, so pipelines can pick a forecaster by configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelConfig {
+    Arima { p: usize, d: usize, q: usize },
+    SimpleExponentialSmoothing { alpha: f64 },
+    Holt { damped: bool },
+    HoltWinters { period: usize, seasonality: Seasonality, damped: bool },
+    LocalLevel,
+    LocalLinearTrend,
+}
+
+impl ModelConfig {
+    /// Build an unfitted model for this configuration
+    pub fn build(&self) -> Box<dyn Forecaster> {
+        match *self {
+            ModelConfig::Arima { p, d, q } => Box::new(ARIMA::new(p, d, q)),
+            ModelConfig::SimpleExponentialSmoothing { alpha } => {
+                Box::new(SimpleExponentialSmoothing::new(alpha))
+            }
+            ModelConfig::Holt { damped } => Box::new(Holt::new(damped)),
+            ModelConfig::HoltWinters { period, seasonality, damped } => {
+                Box::new(HoltWinters::new(period, seasonality, damped))
+            }
+            ModelConfig::LocalLevel => Box::new(LocalLevel::new()),
+            ModelConfig::LocalLinearTrend => Box::new(LocalLinearTrend::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_models_swappable_by_config() {
+        let data = Array1::from(vec![
+            12.0, 13.5, 12.8, 14.1, 15.0, 14.2, 15.8, 16.1, 15.5, 17.0, 17.4, 16.9, 18.2, 18.0,
+        ]);
+        let configs = vec![
+            ModelConfig::Arima { p: 1, d: 1, q: 0 },
+            ModelConfig::SimpleExponentialSmoothing { alpha: 0.4 },
+            ModelConfig::Holt { damped: true },
+            ModelConfig::HoltWinters { period: 4, seasonality: Seasonality::Additive, damped: false },
+            ModelConfig::LocalLevel,
+            ModelConfig::LocalLinearTrend,
+        ];
+
+        for config in configs {
+            let mut model = config.build();
+            model.fit(&data).unwrap();
+
+            assert_eq!(model.predict(3).unwrap().len(), 3);
+
+            let fitted = model.fitted_values().unwrap();
+            let residuals = model.residuals().unwrap();
+            assert_eq!(fitted.len(), residuals.len());
+
+            // fitted + residual reproduces the tail of the training data
+            let offset = data.len() - fitted.len();
+            for i in 0..fitted.len() {
+                assert!((fitted[i] + residuals[i] - data[offset + i]).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unfitted_model_errors() {
+        let model = ModelConfig::SimpleExponentialSmoothing { alpha: 0.5 }.build();
+        assert!(model.predict(1).is_err());
+        assert!(model.residuals().is_err());
+    }
+
+    #[test]
+    fn test_random_walk_interval_widens_with_sqrt_horizon() {
+        let data = Array1::from(vec![1.0, 2.0, 1.5, 3.0, 2.5, 4.0, 3.0, 5.0, 4.5, 6.0]);
+        let mut model = ModelConfig::Arima { p: 0, d: 1, q: 0 }.build();
+        model.fit(&data).unwrap();
+
+        let interval = model.predict_interval(4, 0.95).unwrap();
+        let first = interval.upper[0] - interval.mean[0];
+        for h in 0..4 {
+            let width = interval.upper[h] - interval.mean[h];
+            assert!((width - first * ((h + 1) as f64).sqrt()).abs() < 1e-10);
+            assert!((interval.mean[h] - interval.lower[h] - width).abs() < 1e-10);
+        }
+    }
+}
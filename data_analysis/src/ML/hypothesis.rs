@@ -0,0 +1,520 @@
+// src/ml/hypothesis.rs
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor, Normal, StudentsT};
+
+/// Direction of the alternative hypothesis, for the first sample against the
+/// second (or against the hypothesised mean)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alternative {
+    TwoSided,
+    Less,
+    Greater,
+}
+
+/// Outcome of a hypothesis test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HypothesisTest {
+    pub statistic: f64,
+    pub p_value: f64,
+    pub df: Option<f64>,  // Degrees of freedom of the reference distribution, if it has any
+    pub effect_size: f64,  // Scale depends on the test, see each function
+}
+
+/// Outcome of a one-way analysis of variance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnovaResult {
+    pub statistic: f64,  // F ratio
+    pub p_value: f64,
+    pub df_between: f64,
+    pub df_within: f64,
+    pub eta_squared: f64,  // Share of the total variance explained by the groups
+}
+
+fn mean_and_variance(data: &[f64]) -> (f64, f64) {
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let variance = data.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+fn p_value_from_cdf(cdf: impl Fn(f64) -> f64, statistic: f64, alternative: Alternative) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2.0 * cdf(-statistic.abs())).min(1.0),
+        Alternative::Less => cdf(statistic),
+        Alternative::Greater => cdf(-statistic),
+    }
+}
+
+fn t_result(t: f64, df: f64, alternative: Alternative, effect_size: f64) -> Result<HypothesisTest, String> {
+    let distribution = StudentsT::new(0.0, 1.0, df).map_err(|e| e.to_string())?;
+    Ok(HypothesisTest {
+        statistic: t,
+        p_value: p_value_from_cdf(|x| distribution.cdf(x), t, alternative),
+        df: Some(df),
+        effect_size,
+    })
+}
+
+/// One-sample t-test of whether the mean differs from `mu`.
+///
+/// # Arguments
+///
+/// * `data` - The sample, at least two values
+/// * `mu` - Hypothesised mean
+/// * `alternative` - Direction of the alternative hypothesis
+///
+/// # Returns
+///
+/// The t statistic with `n - 1` degrees of freedom; the effect size is
+/// Cohen's d, `(mean - mu) / sd`
+pub fn t_test_one_sample(data: &[f64], mu: f64, alternative: Alternative) -> Result<HypothesisTest, String> {
+    if data.len() < 2 {
+        return Err("At least two values are needed".to_string());
+    }
+    if data.iter().any(|v| v.is_nan()) {
+        return Err("The sample contains NaN".to_string());
+    }
+    let n = data.len() as f64;
+    let (mean, variance) = mean_and_variance(data);
+    if variance == 0.0 {
+        return Err("The sample has no variance".to_string());
+    }
+    let sd = variance.sqrt();
+    t_result((mean - mu) / (sd / n.sqrt()), n - 1.0, alternative, (mean - mu) / sd)
+}
+
+/// Two-sample t-test of whether two means differ.
+///
+/// # Arguments
+///
+/// * `a` - First sample, at least two values
+/// * `b` - Second sample, at least two values
+/// * `equal_variance` - Pool the variances (Student); otherwise use Welch's
+///   test with Welch-Satterthwaite degrees of freedom
+/// * `alternative` - Direction of the alternative hypothesis
+///
+/// # Returns
+///
+/// The t statistic; the effect size is Cohen's d, scaled by the pooled
+/// standard deviation, or by the root mean variance for Welch's test
+pub fn t_test_two_sample(
+    a: &[f64],
+    b: &[f64],
+    equal_variance: bool,
+    alternative: Alternative,
+) -> Result<HypothesisTest, String> {
+    if a.len() < 2 || b.len() < 2 {
+        return Err("Each sample needs at least two values".to_string());
+    }
+    if a.iter().chain(b.iter()).any(|v| v.is_nan()) {
+        return Err("Samples contain NaN".to_string());
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let (mean1, var1) = mean_and_variance(a);
+    let (mean2, var2) = mean_and_variance(b);
+    if var1 == 0.0 && var2 == 0.0 {
+        return Err("Both samples have no variance".to_string());
+    }
+
+    if equal_variance {
+        let pooled = ((n1 - 1.0) * var1 + (n2 - 1.0) * var2) / (n1 + n2 - 2.0);
+        let t = (mean1 - mean2) / (pooled * (1.0 / n1 + 1.0 / n2)).sqrt();
+        t_result(t, n1 + n2 - 2.0, alternative, (mean1 - mean2) / pooled.sqrt())
+    } else {
+        let (se1, se2) = (var1 / n1, var2 / n2);
+        let t = (mean1 - mean2) / (se1 + se2).sqrt();
+        let df = (se1 + se2).powi(2) / (se1 * se1 / (n1 - 1.0) + se2 * se2 / (n2 - 1.0));
+        t_result(t, df, alternative, (mean1 - mean2) / ((var1 + var2) / 2.0).sqrt())
+    }
+}
+
+/// Paired t-test on the differences `a[i] - b[i]`, e.g. two sensors read at
+/// the same instants.
+///
+/// # Returns
+///
+/// The t statistic with `n - 1` degrees of freedom; the effect size is
+/// Cohen's d_z, the mean difference over its standard deviation
+pub fn t_test_paired(a: &[f64], b: &[f64], alternative: Alternative) -> Result<HypothesisTest, String> {
+    if a.len() != b.len() {
+        return Err("Paired samples must have the same length".to_string());
+    }
+    if a.iter().chain(b.iter()).any(|v| v.is_nan()) {
+        return Err("Samples contain NaN".to_string());
+    }
+    let differences: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+    t_test_one_sample(&differences, 0.0, alternative)
+}
+
+/// Ranks starting at 1, ties sharing their average rank. Also returns the
+/// tie correction term, the sum of `t^3 - t` over groups of `t` ties.
+pub(crate) fn ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap());
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        let t = (end - start) as f64;
+        ties += t * t * t - t;
+        start = end;
+    }
+    (ranks, ties)
+}
+
+/// Mann-Whitney U test (Wilcoxon rank-sum) of whether one sample tends to
+/// take larger values than the other. Needs no normality assumption.
+///
+/// The p-value uses the normal approximation with tie and continuity
+/// corrections, which is reliable from about 8 values per sample.
+///
+/// # Returns
+///
+/// The U statistic of the first sample; the effect size is the rank-biserial
+/// correlation, positive when `a` tends to be larger than `b`
+pub fn mann_whitney_u(a: &[f64], b: &[f64], alternative: Alternative) -> Result<HypothesisTest, String> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Both samples need at least one value".to_string());
+    }
+    if a.iter().chain(b.iter()).any(|v| v.is_nan()) {
+        return Err("Samples contain NaN".to_string());
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let combined: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let (ranks, ties) = ranks(&combined);
+    let rank_sum: f64 = ranks[..a.len()].iter().sum();
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+
+    let mean = n1 * n2 / 2.0;
+    let sigma = (n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)))).sqrt();
+    if sigma == 0.0 {
+        return Err("All values are tied".to_string());
+    }
+    let correction = match alternative {
+        Alternative::TwoSided if u == mean => 0.0,
+        Alternative::TwoSided => 0.5 * (u - mean).signum(),
+        Alternative::Less => -0.5,
+        Alternative::Greater => 0.5,
+    };
+    let z = (u - mean - correction) / sigma;
+    let normal = Normal::new(0.0, 1.0).map_err(|e| e.to_string())?;
+
+    Ok(HypothesisTest {
+        statistic: u,
+        p_value: p_value_from_cdf(|x| normal.cdf(x), z, alternative),
+        df: None,
+        effect_size: 2.0 * u / (n1 * n2) - 1.0,
+    })
+}
+
+/// Survival function of the Kolmogorov distribution
+fn kolmogorov_sf(lambda: f64) -> f64 {
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    for j in 1..=100 {
+        let term = (-2.0 * (j * j) as f64 * lambda * lambda).exp();
+        sum += if j % 2 == 1 { term } else { -term };
+        if term < 1e-12 {
+            break;
+        }
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+fn ks_result(d: f64, effective_n: f64) -> HypothesisTest {
+    // Stephens' small-sample correction of the asymptotic distribution
+    let root = effective_n.sqrt();
+    HypothesisTest {
+        statistic: d,
+        p_value: kolmogorov_sf((root + 0.12 + 0.11 / root) * d),
+        df: None,
+        effect_size: d,
+    }
+}
+
+/// One-sample Kolmogorov-Smirnov test of whether data follow a distribution,
+/// e.g. `statrs::distribution::Normal` with calibrated parameters.
+///
+/// The p-value is two-sided and comes from the asymptotic Kolmogorov
+/// distribution with Stephens' correction. Estimating the distribution's
+/// parameters from the same data makes it conservative.
+///
+/// # Returns
+///
+/// The largest distance D between the empirical and reference CDFs, which
+/// is also reported as the effect size
+pub fn ks_test<D: ContinuousCDF<f64, f64>>(data: &[f64], distribution: &D) -> Result<HypothesisTest, String> {
+    if data.is_empty() {
+        return Err("The sample is empty".to_string());
+    }
+    let mut sorted = data.to_vec();
+    if sorted.iter().any(|v| v.is_nan()) {
+        return Err("The sample contains NaN".to_string());
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len() as f64;
+    let d = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let f = distribution.cdf(x);
+            (f - i as f64 / n).max((i + 1) as f64 / n - f)
+        })
+        .fold(0.0, f64::max);
+    Ok(ks_result(d, n))
+}
+
+/// Two-sample Kolmogorov-Smirnov test of whether two samples come from the
+/// same distribution.
+///
+/// # Returns
+///
+/// The largest distance D between the two empirical CDFs, also reported as
+/// the effect size, with the asymptotic two-sided p-value
+pub fn ks_test_two_sample(a: &[f64], b: &[f64]) -> Result<HypothesisTest, String> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Both samples need at least one value".to_string());
+    }
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    if a.iter().chain(b.iter()).any(|v| v.is_nan()) {
+        return Err("Samples contain NaN".to_string());
+    }
+    a.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+
+    let (mut i, mut j) = (0, 0);
+    let mut d: f64 = 0.0;
+    while i < a.len() && j < b.len() {
+        let x = a[i].min(b[j]);
+        while i < a.len() && a[i] <= x {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= x {
+            j += 1;
+        }
+        d = d.max((i as f64 / n1 - j as f64 / n2).abs());
+    }
+    Ok(ks_result(d, n1 * n2 / (n1 + n2)))
+}
+
+/// Chi-square goodness-of-fit test of observed counts against expected ones.
+///
+/// # Arguments
+///
+/// * `observed` - Counts per category
+/// * `expected` - Expected counts or proportions per category, rescaled to the observed total
+/// * `ddof` - Parameters estimated from the data, removed from the degrees of freedom
+///
+/// # Returns
+///
+/// The chi-square statistic with `k - 1 - ddof` degrees of freedom; the
+/// effect size is Cohen's w, `sqrt(chi2 / N)`
+pub fn chi_square_goodness_of_fit(observed: &[f64], expected: &[f64], ddof: usize) -> Result<HypothesisTest, String> {
+    if observed.len() != expected.len() {
+        return Err("Observed and expected must have the same categories".to_string());
+    }
+    if observed.len() < ddof + 2 {
+        return Err("Not enough categories for the degrees of freedom".to_string());
+    }
+    if expected.iter().chain(observed.iter()).any(|v| v.is_nan()) {
+        return Err("Counts contain NaN".to_string());
+    }
+    if expected.iter().any(|&e| e <= 0.0) || observed.iter().any(|&o| o < 0.0) {
+        return Err("Expected counts must be positive and observed counts non-negative".to_string());
+    }
+    let total: f64 = observed.iter().sum();
+    let scale = total / expected.iter().sum::<f64>();
+    let statistic: f64 = observed
+        .iter()
+        .zip(expected.iter())
+        .map(|(o, e)| (o - e * scale).powi(2) / (e * scale))
+        .sum();
+    let df = (observed.len() - 1 - ddof) as f64;
+    let distribution = ChiSquared::new(df).map_err(|e| e.to_string())?;
+
+    Ok(HypothesisTest {
+        statistic,
+        p_value: distribution.sf(statistic),
+        df: Some(df),
+        effect_size: (statistic / total).sqrt(),
+    })
+}
+
+/// One-way ANOVA of whether several groups share the same mean, e.g. the
+/// same sensor model across calibration batches.
+///
+/// # Arguments
+///
+/// * `groups` - At least two groups, with more values in total than groups
+///
+/// # Returns
+///
+/// The F ratio, its p-value and eta squared
+pub fn one_way_anova(groups: &[&[f64]]) -> Result<AnovaResult, String> {
+    if groups.len() < 2 || groups.iter().any(|g| g.is_empty()) {
+        return Err("At least two non-empty groups are needed".to_string());
+    }
+    let n: usize = groups.iter().map(|g| g.len()).sum();
+    if n <= groups.len() {
+        return Err("Need more values than groups".to_string());
+    }
+    if groups.iter().any(|g| g.iter().any(|v| v.is_nan())) {
+        return Err("Groups contain NaN".to_string());
+    }
+    let grand_mean = groups.iter().flat_map(|g| g.iter()).sum::<f64>() / n as f64;
+
+    let mut between = 0.0;
+    let mut within = 0.0;
+    for group in groups {
+        let mean = group.iter().sum::<f64>() / group.len() as f64;
+        between += group.len() as f64 * (mean - grand_mean).powi(2);
+        within += group.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    }
+    if within == 0.0 {
+        return Err("Groups have no within-group variance".to_string());
+    }
+    let df_between = (groups.len() - 1) as f64;
+    let df_within = (n - groups.len()) as f64;
+    let statistic = (between / df_between) / (within / df_within);
+    let distribution = FisherSnedecor::new(df_between, df_within).map_err(|e| e.to_string())?;
+
+    Ok(AnovaResult {
+        statistic,
+        p_value: distribution.sf(statistic),
+        df_between,
+        df_within,
+        eta_squared: between / (between + within),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Student's sleep data (Cushny and Peebles), extra hours of sleep under two drugs
+    const SLEEP_1: [f64; 10] = [0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0];
+    const SLEEP_2: [f64; 10] = [1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4];
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn test_t_tests_match_sleep_data() {
+        // Reference values from R's t.test on the sleep data
+        let one = t_test_one_sample(&SLEEP_1, 0.0, Alternative::TwoSided).unwrap();
+        assert!(close(one.statistic, 1.3257, 1e-4));
+        assert!(close(one.p_value, 0.2176, 1e-4));
+        assert_eq!(one.df, Some(9.0));
+
+        let welch = t_test_two_sample(&SLEEP_1, &SLEEP_2, false, Alternative::TwoSided).unwrap();
+        assert!(close(welch.statistic, -1.8608, 1e-4));
+        assert!(close(welch.df.unwrap(), 17.776, 1e-3));
+        assert!(close(welch.p_value, 0.07939, 1e-5));
+
+        let student = t_test_two_sample(&SLEEP_1, &SLEEP_2, true, Alternative::TwoSided).unwrap();
+        assert!(close(student.p_value, 0.07919, 1e-5));
+        let less = t_test_two_sample(&SLEEP_1, &SLEEP_2, true, Alternative::Less).unwrap();
+        assert!(close(less.p_value, student.p_value / 2.0, 1e-12));
+        assert!(student.effect_size < -0.8);
+
+        let paired = t_test_paired(&SLEEP_1, &SLEEP_2, Alternative::TwoSided).unwrap();
+        assert!(close(paired.statistic, -4.0621, 1e-4));
+        assert!(close(paired.p_value, 0.002833, 1e-6));
+        assert!(close(paired.effect_size, -1.2845, 1e-4));
+
+        assert!(t_test_paired(&SLEEP_1, &SLEEP_2[..9], Alternative::TwoSided).is_err());
+
+        let mut gappy = SLEEP_1;
+        gappy[3] = f64::NAN;
+        assert!(t_test_one_sample(&gappy, 0.0, Alternative::TwoSided).is_err());
+        assert!(t_test_two_sample(&gappy, &SLEEP_2, false, Alternative::TwoSided).is_err());
+        assert!(t_test_paired(&SLEEP_1, &gappy, Alternative::TwoSided).is_err());
+    }
+
+    #[test]
+    fn test_mann_whitney_matches_sleep_data() {
+        // R: wilcox.test(extra ~ group, data = sleep) gives W = 25.5, p = 0.06933
+        let test = mann_whitney_u(&SLEEP_1, &SLEEP_2, Alternative::TwoSided).unwrap();
+        assert_eq!(test.statistic, 25.5);
+        assert!(close(test.p_value, 0.06933, 1e-5));
+        assert!(close(test.effect_size, -0.49, 1e-12));
+
+        let greater = mann_whitney_u(&SLEEP_2, &SLEEP_1, Alternative::Greater).unwrap();
+        assert!(close(greater.p_value, 0.06933 / 2.0, 1e-5));
+
+        // U exactly at its mean: no continuity correction, p = 1
+        let centered = mann_whitney_u(&[1.0, 4.0], &[2.0, 3.0], Alternative::TwoSided).unwrap();
+        assert_eq!(centered.statistic, 2.0);
+        assert_eq!(centered.p_value, 1.0);
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov() {
+        // Published critical values of the Kolmogorov distribution
+        assert!(close(kolmogorov_sf(1.3581), 0.05, 1e-4));
+        assert!(close(kolmogorov_sf(1.6276), 0.01, 1e-4));
+
+        // Evenly spread quantiles of a normal fit it closely
+        let normal = Normal::new(20.0, 2.0).unwrap();
+        let quantiles: Vec<f64> = (0..50).map(|i| normal.inverse_cdf((i as f64 + 0.5) / 50.0)).collect();
+        let fit = ks_test(&quantiles, &normal).unwrap();
+        assert!(close(fit.statistic, 0.01, 1e-9));
+        assert!(fit.p_value > 0.99);
+        let shifted = ks_test(&quantiles, &Normal::new(22.0, 2.0).unwrap()).unwrap();
+        assert!(shifted.p_value < 1e-4);
+
+        let a: Vec<f64> = (0..40).map(|i| i as f64).collect();
+        let b: Vec<f64> = (0..40).map(|i| i as f64 + 20.0).collect();
+        let two = ks_test_two_sample(&a, &b).unwrap();
+        assert!(close(two.statistic, 0.5, 1e-12));
+        assert!(two.p_value < 1e-3);
+        assert!(ks_test_two_sample(&a, &a).unwrap().p_value > 0.99);
+    }
+
+    #[test]
+    fn test_chi_square_matches_mendel() {
+        // Mendel's peas against the 9:3:3:1 ratio, R gives X-squared = 0.47002, p = 0.9254
+        let test = chi_square_goodness_of_fit(&[315.0, 108.0, 101.0, 32.0], &[9.0, 3.0, 3.0, 1.0], 0).unwrap();
+        assert!(close(test.statistic, 0.47002, 1e-5));
+        assert!(close(test.p_value, 0.9254, 1e-4));
+        assert_eq!(test.df, Some(3.0));
+        assert!(close(test.effect_size, (0.470024 / 556.0f64).sqrt(), 1e-5));
+
+        assert!(chi_square_goodness_of_fit(&[1.0, 2.0], &[1.0], 0).is_err());
+        assert!(chi_square_goodness_of_fit(&[1.0, f64::NAN], &[1.0, 1.0], 0).is_err());
+
+        // Far in the tail, where 1 - cdf would round to 0: X-squared = 100 on 3 df
+        let extreme = chi_square_goodness_of_fit(&[50.0, 0.0, 0.0, 50.0], &[1.0; 4], 0).unwrap();
+        assert!(close(extreme.statistic, 100.0, 1e-12));
+        assert!(close(extreme.p_value / 1.5541594313896e-21, 1.0, 1e-6));
+    }
+
+    #[test]
+    fn test_anova_matches_plant_growth() {
+        // R's PlantGrowth data: F = 4.846 on 2 and 27 df, p = 0.01591
+        let ctrl = [4.17, 5.58, 5.18, 6.11, 4.50, 4.61, 5.17, 4.53, 5.33, 5.14];
+        let trt1 = [4.81, 4.17, 4.41, 3.59, 5.87, 3.83, 6.03, 4.89, 4.32, 4.69];
+        let trt2 = [6.31, 5.12, 5.54, 5.50, 5.37, 5.29, 4.92, 6.15, 5.80, 5.26];
+        let result = one_way_anova(&[&ctrl, &trt1, &trt2]).unwrap();
+        assert!(close(result.statistic, 4.846, 1e-3));
+        assert!(close(result.p_value, 0.01591, 1e-5));
+        assert_eq!((result.df_between, result.df_within), (2.0, 27.0));
+        assert!(close(result.eta_squared, 3.76634 / (3.76634 + 10.49209), 1e-5));
+
+        assert!(one_way_anova(&[&ctrl]).is_err());
+        assert!(one_way_anova(&[&ctrl, &[4.0, f64::NAN]]).is_err());
+    }
+}
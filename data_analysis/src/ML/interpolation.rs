@@ -0,0 +1,332 @@
+// src/ml/interpolation.rs
+use chrono::{Duration, NaiveDateTime};
+
+use crate::ML::data_imputation::Imputed;
+
+/// How values between observations are reconstructed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationMethod {
+    /// Straight line between the surrounding observations, weighted by elapsed time
+    Linear,
+    /// Value of the observation closest in time
+    Nearest,
+    /// Last observation carried forward (LOCF)
+    Previous,
+    /// Next observation carried backward (NOCB)
+    Next,
+    /// Natural cubic spline, smooth but may overshoot
+    CubicSpline,
+    /// Piecewise cubic Hermite (Fritsch-Carlson), smooth without overshooting
+    Pchip,
+}
+
+/// Seconds since `origin`, with millisecond resolution
+fn seconds_since(origin: NaiveDateTime, time: NaiveDateTime) -> f64 {
+    (time - origin).num_milliseconds() as f64 / 1000.0
+}
+
+/// Interpolant through observations at increasing times (in seconds)
+struct Interpolant {
+    method: InterpolationMethod,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    slopes: Vec<f64>,  // Second derivatives for splines, first derivatives for PCHIP
+}
+
+impl Interpolant {
+    fn new(method: InterpolationMethod, x: Vec<f64>, y: Vec<f64>) -> Self {
+        let slopes = match method {
+            InterpolationMethod::CubicSpline if x.len() > 2 => spline_second_derivatives(&x, &y),
+            InterpolationMethod::Pchip if x.len() > 1 => pchip_derivatives(&x, &y),
+            _ => Vec::new(),
+        };
+        Interpolant { method, x, y, slopes }
+    }
+
+    /// Value at `t`; NaN outside the observed range except for `Nearest`
+    fn evaluate(&self, t: f64) -> f64 {
+        let n = self.x.len();
+        let (first, last) = (self.x[0], self.x[n - 1]);
+        if self.method == InterpolationMethod::Nearest {
+            let i = self.x.partition_point(|&x| x < t);
+            return match i {
+                0 => self.y[0],
+                i if i == n => self.y[n - 1],
+                i if t - self.x[i - 1] <= self.x[i] - t => self.y[i - 1],
+                i => self.y[i],
+            };
+        }
+        if t < first || t > last {
+            return f64::NAN;
+        }
+
+        // Interval [x[k], x[k + 1]] containing t, or the exact knot
+        let i = self.x.partition_point(|&x| x < t);
+        if i < n && self.x[i] == t {
+            return self.y[i];
+        }
+        let k = i - 1;
+        let h = self.x[k + 1] - self.x[k];
+        let u = (t - self.x[k]) / h;
+
+        match self.method {
+            InterpolationMethod::Previous => self.y[k],
+            InterpolationMethod::Next => self.y[k + 1],
+            InterpolationMethod::CubicSpline if !self.slopes.is_empty() => {
+                let (m0, m1) = (self.slopes[k], self.slopes[k + 1]);
+                let (a, b) = (1.0 - u, u);
+                a * self.y[k] + b * self.y[k + 1] + ((a * a * a - a) * m0 + (b * b * b - b) * m1) * h * h / 6.0
+            }
+            InterpolationMethod::Pchip => {
+                let (d0, d1) = (self.slopes[k], self.slopes[k + 1]);
+                let (u2, u3) = (u * u, u * u * u);
+                (2.0 * u3 - 3.0 * u2 + 1.0) * self.y[k]
+                    + (u3 - 2.0 * u2 + u) * h * d0
+                    + (-2.0 * u3 + 3.0 * u2) * self.y[k + 1]
+                    + (u3 - u2) * h * d1
+            }
+            // Linear, and splines through only two points
+            _ => self.y[k] + u * (self.y[k + 1] - self.y[k]),
+        }
+    }
+}
+
+/// Second derivatives of the natural cubic spline, by the Thomas algorithm
+fn spline_second_derivatives(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut diagonal = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+        rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h[i] - (y[i] - y[i - 1]) / h[i - 1]);
+    }
+
+    // Forward sweep over the interior rows, the end rows are fixed at zero
+    for i in 2..n - 1 {
+        let factor = h[i - 1] / diagonal[i - 1];
+        diagonal[i] -= factor * h[i - 1];
+        rhs[i] -= factor * rhs[i - 1];
+    }
+    let mut second = vec![0.0; n];
+    for i in (1..n - 1).rev() {
+        second[i] = (rhs[i] - h[i] * second[i + 1]) / diagonal[i];
+    }
+    second
+}
+
+/// Fritsch-Carlson derivatives that keep the interpolant monotone between knots
+fn pchip_derivatives(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+    let delta: Vec<f64> = (0..n - 1).map(|k| (y[k + 1] - y[k]) / h[k]).collect();
+    if n == 2 {
+        return vec![delta[0], delta[0]];
+    }
+
+    let mut d = vec![0.0; n];
+    for k in 1..n - 1 {
+        if delta[k - 1] * delta[k] > 0.0 {
+            // Weighted harmonic mean of the neighbouring slopes
+            let w1 = 2.0 * h[k] + h[k - 1];
+            let w2 = h[k] + 2.0 * h[k - 1];
+            d[k] = (w1 + w2) / (w1 / delta[k - 1] + w2 / delta[k]);
+        }
+    }
+
+    // One-sided three-point estimates at the ends, limited to preserve shape
+    let edge = |h0: f64, h1: f64, m0: f64, m1: f64| {
+        let d = ((2.0 * h0 + h1) * m0 - h0 * m1) / (h0 + h1);
+        if d.signum() != m0.signum() {
+            0.0
+        } else if m0.signum() != m1.signum() && d.abs() > 3.0 * m0.abs() {
+            3.0 * m0
+        } else {
+            d
+        }
+    };
+    d[0] = edge(h[0], h[1], delta[0], delta[1]);
+    d[n - 1] = edge(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]);
+    d
+}
+
+/// Observed (non-NaN) points as seconds since the first timestamp
+fn observed_points(times: &[NaiveDateTime], values: &[f64]) -> Result<(NaiveDateTime, Vec<f64>, Vec<f64>), String> {
+    if times.len() != values.len() {
+        return Err("Timestamps and values must have the same length".to_string());
+    }
+    if times.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("Timestamps must be strictly increasing".to_string());
+    }
+    let (x, y): (Vec<f64>, Vec<f64>) = times
+        .iter()
+        .zip(values)
+        .filter(|(_, v)| !v.is_nan())
+        .map(|(&t, &v)| (seconds_since(times[0], t), v))
+        .unzip();
+    if x.is_empty() {
+        return Err("Cannot interpolate a series without observed values".to_string());
+    }
+    Ok((times[0], x, y))
+}
+
+/// Interpolate an irregularly sampled series at arbitrary times.
+///
+/// # Arguments
+///
+/// * `times` - Strictly increasing timestamps of the observations
+/// * `values` - Observed values, NaN marks a missing reading
+/// * `targets` - Times at which to evaluate the series
+/// * `method` - Interpolation method
+///
+/// # Returns
+///
+/// One value per target; NaN outside the observed time range, except with `Nearest`
+pub fn interpolate_at(
+    times: &[NaiveDateTime],
+    values: &[f64],
+    targets: &[NaiveDateTime],
+    method: InterpolationMethod,
+) -> Result<Vec<f64>, String> {
+    let (origin, x, y) = observed_points(times, values)?;
+    let interpolant = Interpolant::new(method, x, y);
+    Ok(targets.iter().map(|&t| interpolant.evaluate(seconds_since(origin, t))).collect())
+}
+
+/// Fill the NaN readings of an irregularly sampled series using the time
+/// elapsed between readings.
+///
+/// Gaps before the first or after the last reading stay NaN unless `method`
+/// is `Nearest`, and only filled positions are listed as imputed.
+pub fn fill_gaps(times: &[NaiveDateTime], values: &[f64], method: InterpolationMethod) -> Result<Imputed, String> {
+    let (origin, x, y) = observed_points(times, values)?;
+    let interpolant = Interpolant::new(method, x, y);
+
+    let mut filled = values.to_vec();
+    let mut imputed = Vec::new();
+    for (i, value) in filled.iter_mut().enumerate() {
+        if value.is_nan() {
+            *value = interpolant.evaluate(seconds_since(origin, times[i]));
+            if !value.is_nan() {
+                imputed.push(i);
+            }
+        }
+    }
+    Ok(Imputed { values: filled, imputed })
+}
+
+/// Resample an irregular series onto a regular grid.
+///
+/// # Arguments
+///
+/// * `times` - Strictly increasing timestamps of the observations
+/// * `values` - Observed values, NaN marks a missing reading
+/// * `interval` - Spacing of the grid, which starts at the first timestamp
+/// * `method` - Interpolation method
+///
+/// # Returns
+///
+/// The grid timestamps, up to the last observation, and the values on them
+pub fn resample(
+    times: &[NaiveDateTime],
+    values: &[f64],
+    interval: Duration,
+    method: InterpolationMethod,
+) -> Result<(Vec<NaiveDateTime>, Vec<f64>), String> {
+    if interval <= Duration::zero() {
+        return Err("Resampling interval must be positive".to_string());
+    }
+    if times.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let end = times[times.len() - 1];
+    let mut grid = Vec::new();
+    let mut t = times[0];
+    while t <= end {
+        grid.push(t);
+        t += interval;
+    }
+    let resampled = interpolate_at(times, values, &grid, method)?;
+    Ok((grid, resampled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(seconds: i64) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(8, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    #[test]
+    fn test_linear_weights_by_elapsed_time() {
+        let times = [at(0), at(10), at(40)];
+        let values = [0.0, 10.0, 40.0];
+        let result = interpolate_at(&times, &values, &[at(20), at(35), at(50)], InterpolationMethod::Linear).unwrap();
+        assert!((result[0] - 20.0).abs() < 1e-12);
+        assert!((result[1] - 35.0).abs() < 1e-12);
+        assert!(result[2].is_nan());
+    }
+
+    #[test]
+    fn test_step_methods() {
+        let times = [at(0), at(10), at(40)];
+        let values = [1.0, 2.0, 3.0];
+        let targets = [at(5), at(24), at(26)];
+        let previous = interpolate_at(&times, &values, &targets, InterpolationMethod::Previous).unwrap();
+        let next = interpolate_at(&times, &values, &targets, InterpolationMethod::Next).unwrap();
+        let nearest = interpolate_at(&times, &values, &targets, InterpolationMethod::Nearest).unwrap();
+        assert_eq!(previous, vec![1.0, 2.0, 2.0]);
+        assert_eq!(next, vec![2.0, 3.0, 3.0]);
+        assert_eq!(nearest, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_natural_spline_by_hand() {
+        let times = [at(0), at(1), at(2)];
+        let values = [0.0, 1.0, 0.0];
+        let result = interpolate_at(&times, &values, &[at(0), at(1)], InterpolationMethod::CubicSpline).unwrap();
+        assert_eq!(result, vec![0.0, 1.0]);
+
+        // Second derivative at the middle knot is -3
+        let half = at(0) + Duration::milliseconds(500);
+        let value = interpolate_at(&times, &values, &[half], InterpolationMethod::CubicSpline).unwrap()[0];
+        assert!((value - 0.6875).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pchip_does_not_overshoot() {
+        let times: Vec<NaiveDateTime> = (0..6).map(|i| at(i * 10)).collect();
+        let values = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let targets: Vec<NaiveDateTime> = (0..50).map(at).collect();
+
+        let pchip = interpolate_at(&times, &values, &targets, InterpolationMethod::Pchip).unwrap();
+        assert!(pchip.iter().all(|&v| (0.0..=1.0).contains(&v)));
+        assert!(pchip.windows(2).all(|w| w[1] >= w[0]));
+
+        let spline = interpolate_at(&times, &values, &targets, InterpolationMethod::CubicSpline).unwrap();
+        assert!(spline.iter().any(|&v| !(0.0..=1.0).contains(&v)));
+    }
+
+    #[test]
+    fn test_fill_gaps_and_resample() {
+        let times = [at(0), at(30), at(40), at(100)];
+        let values = [10.0, f64::NAN, 14.0, 20.0];
+        let filled = fill_gaps(&times, &values, InterpolationMethod::Linear).unwrap();
+        assert!((filled.values[1] - 13.0).abs() < 1e-12);
+        assert_eq!(filled.imputed, vec![1]);
+
+        let (grid, resampled) = resample(&times, &values, Duration::seconds(25), InterpolationMethod::Linear).unwrap();
+        assert_eq!(grid, vec![at(0), at(25), at(50), at(75), at(100)]);
+        assert!((resampled[1] - 12.5).abs() < 1e-12);
+        assert!((resampled[2] - 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unsorted_timestamps_rejected() {
+        let times = [at(10), at(0)];
+        assert!(interpolate_at(&times, &[1.0, 2.0], &[at(5)], InterpolationMethod::Linear).is_err());
+    }
+}
@@ -0,0 +1,652 @@
+// src/ml/kalman.rs
+use ndarray::{s, Array1, Array2, Axis};
+
+use crate::ML::forecaster::Forecaster;
+use crate::ML::optimize::nelder_mead;
+
+/// Initial state variance used to approximate a diffuse prior
+const DIFFUSE_VARIANCE: f64 = 1e7;
+
+/// Linear Gaussian state-space model
+///
+/// ```text
+/// y_t     = Z a_t + e_t,      e_t ~ N(0, H)
+/// a_{t+1} = T a_t + u_t,      u_t ~ N(0, Q)
+/// a_1     ~ N(initial_state, initial_cov)
+/// ```
+///
+/// Observations are the rows of an `Array2` (one column per observed
+/// variable). NaN entries are treated as missing and skipped by the update.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSpaceModel {
+    pub transition: Array2<f64>,  // T, states x states
+    pub observation: Array2<f64>,  // Z, observed variables x states
+    pub state_cov: Array2<f64>,  // Q
+    pub obs_cov: Array2<f64>,  // H
+    pub initial_state: Array1<f64>,
+    pub initial_cov: Array2<f64>,
+    pub diffuse_steps: usize,  // Observed steps left out of the log-likelihood
+}
+
+/// Output of the Kalman filter
+#[derive(Debug, Clone)]
+pub struct FilterOutput {
+    pub predicted_states: Array2<f64>,  // a_{t|t-1}, one row per time step
+    pub predicted_covs: Vec<Array2<f64>>,
+    pub filtered_states: Array2<f64>,  // a_{t|t}
+    pub filtered_covs: Vec<Array2<f64>>,
+    pub innovations: Array2<f64>,  // y_t - Z a_{t|t-1}, NaN where y_t is missing
+    pub innovation_covs: Vec<Array2<f64>>,  // Z P_{t|t-1} Z' + H
+    pub log_likelihood: f64,
+}
+
+/// Output of the Rauch-Tung-Striebel smoother
+#[derive(Debug, Clone)]
+pub struct SmootherOutput {
+    pub states: Array2<f64>,  // a_{t|n}
+    pub covs: Vec<Array2<f64>>,
+}
+
+impl StateSpaceModel {
+    /// Create a model, checking that the matrix dimensions agree
+    pub fn new(
+        transition: Array2<f64>,
+        observation: Array2<f64>,
+        state_cov: Array2<f64>,
+        obs_cov: Array2<f64>,
+        initial_state: Array1<f64>,
+        initial_cov: Array2<f64>,
+    ) -> Result<Self, String> {
+        let k = transition.nrows();
+        let m = observation.nrows();
+        if transition.ncols() != k || observation.ncols() != k {
+            return Err("Transition must be square and match the observation matrix columns".to_string());
+        }
+        if state_cov.dim() != (k, k) || initial_cov.dim() != (k, k) || initial_state.len() != k {
+            return Err("State covariances and initial state must match the number of states".to_string());
+        }
+        if obs_cov.dim() != (m, m) {
+            return Err("Observation covariance must match the number of observed variables".to_string());
+        }
+
+        Ok(StateSpaceModel {
+            transition,
+            observation,
+            state_cov,
+            obs_cov,
+            initial_state,
+            initial_cov,
+            diffuse_steps: 0,
+        })
+    }
+
+    fn n_states(&self) -> usize {
+        self.transition.nrows()
+    }
+
+    /// Run the Kalman filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `observations` - One row per time step, NaN marks a missing value
+    ///
+    /// # Returns
+    ///
+    /// Predicted and filtered states with their covariances, the innovations
+    /// and the Gaussian log-likelihood
+    pub fn filter(&self, observations: &Array2<f64>) -> Result<FilterOutput, String> {
+        let n = observations.nrows();
+        let k = self.n_states();
+        let m = self.observation.nrows();
+        if observations.ncols() != m {
+            return Err("Observations must have one column per observed variable".to_string());
+        }
+
+        let mut output = FilterOutput {
+            predicted_states: Array2::zeros((n, k)),
+            predicted_covs: Vec::with_capacity(n),
+            filtered_states: Array2::zeros((n, k)),
+            filtered_covs: Vec::with_capacity(n),
+            innovations: Array2::from_elem((n, m), f64::NAN),
+            innovation_covs: Vec::with_capacity(n),
+            log_likelihood: 0.0,
+        };
+
+        let mut state = self.initial_state.clone();
+        let mut cov = self.initial_cov.clone();
+        let mut observed_steps = 0;
+
+        for t in 0..n {
+            output.predicted_states.row_mut(t).assign(&state);
+            output.innovation_covs.push(self.observation.dot(&cov).dot(&self.observation.t()) + &self.obs_cov);
+            output.predicted_covs.push(cov.clone());
+
+            let row = observations.row(t);
+            let observed: Vec<usize> = (0..m).filter(|&j| !row[j].is_nan()).collect();
+
+            if !observed.is_empty() {
+                // Update with the observed rows of Z and H only
+                let z = self.observation.select(Axis(0), &observed);
+                let h = self.obs_cov.select(Axis(0), &observed).select(Axis(1), &observed);
+                let y = Array1::from_shape_fn(observed.len(), |i| row[observed[i]]);
+
+                let innovation = &y - &z.dot(&state);
+                let pz = cov.dot(&z.t());
+                let f = z.dot(&pz) + &h;
+                let (f_inv, log_det) = spd_inverse(&f)
+                    .ok_or_else(|| format!("Innovation covariance is not positive definite at t = {}", t))?;
+
+                let gain = pz.dot(&f_inv);
+                state = &state + &gain.dot(&innovation);
+                cov = &cov - &gain.dot(&pz.t());
+                cov = symmetrize(&cov);
+
+                for (i, &j) in observed.iter().enumerate() {
+                    output.innovations[[t, j]] = innovation[i];
+                }
+                if observed_steps >= self.diffuse_steps {
+                    let quadratic = innovation.dot(&f_inv.dot(&innovation));
+                    output.log_likelihood -= 0.5
+                        * (observed.len() as f64 * (2.0 * std::f64::consts::PI).ln() + log_det + quadratic);
+                }
+                observed_steps += 1;
+            }
+
+            output.filtered_states.row_mut(t).assign(&state);
+            output.filtered_covs.push(cov.clone());
+
+            state = self.transition.dot(&state);
+            cov = symmetrize(&(self.transition.dot(&cov).dot(&self.transition.t()) + &self.state_cov));
+        }
+
+        Ok(output)
+    }
+
+    /// Run the filter followed by the Rauch-Tung-Striebel smoother, giving
+    /// state estimates that use every observation.
+    pub fn smooth(&self, observations: &Array2<f64>) -> Result<SmootherOutput, String> {
+        let filtered = self.filter(observations)?;
+        self.smooth_filtered(&filtered)
+    }
+
+    /// Smooth the output of an earlier `filter` call
+    pub fn smooth_filtered(&self, filtered: &FilterOutput) -> Result<SmootherOutput, String> {
+        let n = filtered.filtered_states.nrows();
+        let mut states = filtered.filtered_states.clone();
+        let mut covs = filtered.filtered_covs.clone();
+        if n == 0 {
+            return Ok(SmootherOutput { states, covs });
+        }
+
+        for t in (0..n - 1).rev() {
+            let (predicted_inv, _) = spd_inverse(&filtered.predicted_covs[t + 1])
+                .ok_or_else(|| format!("Predicted covariance is singular at t = {}", t + 1))?;
+            let gain = filtered.filtered_covs[t].dot(&self.transition.t()).dot(&predicted_inv);
+
+            let state_step = &states.row(t + 1) - &filtered.predicted_states.row(t + 1);
+            let state = &filtered.filtered_states.row(t) + &gain.dot(&state_step);
+            states.row_mut(t).assign(&state);
+
+            let cov_step = &covs[t + 1] - &filtered.predicted_covs[t + 1];
+            covs[t] = symmetrize(&(&filtered.filtered_covs[t] + &gain.dot(&cov_step).dot(&gain.t())));
+        }
+
+        Ok(SmootherOutput { states, covs })
+    }
+
+    /// Forecast past the end of a filtered series.
+    ///
+    /// # Returns
+    ///
+    /// The expected observations (one row per step) and their covariance matrices
+    pub fn forecast(&self, filtered: &FilterOutput, steps: usize) -> Result<(Array2<f64>, Vec<Array2<f64>>), String> {
+        let n = filtered.filtered_states.nrows();
+        let (mut state, mut cov) = if n == 0 {
+            (self.initial_state.clone(), self.initial_cov.clone())
+        } else {
+            let state = self.transition.dot(&filtered.filtered_states.row(n - 1));
+            let cov = self.transition.dot(&filtered.filtered_covs[n - 1]).dot(&self.transition.t()) + &self.state_cov;
+            (state, cov)
+        };
+
+        let mut means = Array2::zeros((steps, self.observation.nrows()));
+        let mut covs = Vec::with_capacity(steps);
+        for h in 0..steps {
+            means.row_mut(h).assign(&self.observation.dot(&state));
+            covs.push(self.observation.dot(&cov).dot(&self.observation.t()) + &self.obs_cov);
+            state = self.transition.dot(&state);
+            cov = self.transition.dot(&cov).dot(&self.transition.t()) + &self.state_cov;
+        }
+
+        Ok((means, covs))
+    }
+}
+
+/// Lower Cholesky factor of a symmetric positive definite matrix
+fn cholesky(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let mut l = Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum();
+            if i == j {
+                let diagonal = a[[i, i]] - sum;
+                if diagonal <= 0.0 || !diagonal.is_finite() {
+                    return None;
+                }
+                l[[i, i]] = diagonal.sqrt();
+            } else {
+                l[[i, j]] = (a[[i, j]] - sum) / l[[j, j]];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Inverse and log-determinant of a symmetric positive definite matrix
+fn spd_inverse(a: &Array2<f64>) -> Option<(Array2<f64>, f64)> {
+    let l = cholesky(a)?;
+    let n = l.nrows();
+
+    // Invert the triangular factor by forward substitution
+    let mut l_inv = Array2::<f64>::zeros((n, n));
+    for col in 0..n {
+        for i in col..n {
+            let identity = if i == col { 1.0 } else { 0.0 };
+            let sum: f64 = (col..i).map(|k| l[[i, k]] * l_inv[[k, col]]).sum();
+            l_inv[[i, col]] = (identity - sum) / l[[i, i]];
+        }
+    }
+
+    let log_det = 2.0 * l.diag().iter().map(|d| d.ln()).sum::<f64>();
+    Some((l_inv.t().dot(&l_inv), log_det))
+}
+
+fn symmetrize(a: &Array2<f64>) -> Array2<f64> {
+    (a + &a.t()) / 2.0
+}
+
+/// Local level or local linear trend model, the shared part of both structural models
+#[derive(Debug, Clone, Copy)]
+struct StructuralSpec {
+    trend: bool,
+}
+
+/// Fitted structural model
+#[derive(Debug, Clone)]
+struct StructuralFit {
+    variances: Vec<f64>,  // Observation noise first, then level (and slope) disturbances
+    model: StateSpaceModel,
+    filtered: FilterOutput,
+    history: Array1<f64>,
+    start: usize,  // Time step after the last diffuse one, where fitted values begin
+}
+
+impl StructuralSpec {
+    fn n_states(&self) -> usize {
+        if self.trend { 2 } else { 1 }
+    }
+
+    fn build(&self, variances: &[f64], first: f64) -> StateSpaceModel {
+        let k = self.n_states();
+        let transition = if self.trend {
+            Array2::from_shape_vec((2, 2), vec![1.0, 1.0, 0.0, 1.0]).unwrap()
+        } else {
+            Array2::eye(1)
+        };
+        let mut observation = Array2::zeros((1, k));
+        observation[[0, 0]] = 1.0;
+
+        let mut initial_state = Array1::zeros(k);
+        initial_state[0] = first;
+
+        StateSpaceModel {
+            transition,
+            observation,
+            state_cov: Array2::from_diag(&Array1::from(variances[1..].to_vec())),
+            obs_cov: Array2::from_elem((1, 1), variances[0]),
+            initial_state,
+            initial_cov: Array2::eye(k) * DIFFUSE_VARIANCE,
+            diffuse_steps: k,
+        }
+    }
+
+    /// Maximum likelihood estimates of the variances, optimised on the log scale
+    fn fit(&self, data: &Array1<f64>) -> Result<StructuralFit, String> {
+        let observed: Vec<f64> = data.iter().copied().filter(|y| !y.is_nan()).collect();
+        if observed.len() < self.n_states() + 3 {
+            return Err("Too few observed values to fit the structural model".to_string());
+        }
+        let first = observed[0];
+        // The diffuse steps count observed values, so leading gaps push the start back
+        let start = data
+            .iter()
+            .enumerate()
+            .filter(|(_, y)| !y.is_nan())
+            .nth(self.n_states())
+            .map_or(data.len(), |(t, _)| t);
+        let observations = data.clone().insert_axis(Axis(1));
+
+        // Start from half the variance of the observed differences for every component
+        let diffs: Vec<f64> = observed.windows(2).map(|w| w[1] - w[0]).collect();
+        let mean = diffs.iter().sum::<f64>() / diffs.len() as f64;
+        let spread = diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / diffs.len() as f64;
+        let scale = (spread / 2.0).max(1e-8).ln();
+
+        let negative_log_likelihood = |log_variances: &[f64]| {
+            let variances: Vec<f64> = log_variances.iter().map(|v| (v + scale).exp()).collect();
+            match self.build(&variances, first).filter(&observations) {
+                Ok(output) => -output.log_likelihood,
+                Err(_) => f64::INFINITY,
+            }
+        };
+        let (best, _) = nelder_mead(negative_log_likelihood, &vec![0.0; self.n_states() + 1], 1.0, 2000, 1e-10);
+
+        let variances: Vec<f64> = best.iter().map(|v| (v + scale).exp()).collect();
+        let model = self.build(&variances, first);
+        let filtered = model.filter(&observations)?;
+        Ok(StructuralFit { variances, model, filtered, history: data.clone(), start })
+    }
+}
+
+impl StructuralFit {
+    fn forecast(&self, horizon: usize) -> Result<(Array1<f64>, Array1<f64>), String> {
+        let (means, covs) = self.model.forecast(&self.filtered, horizon)?;
+        Ok((means.column(0).to_owned(), covs.iter().map(|c| c[[0, 0]]).collect()))
+    }
+
+    fn fitted_values(&self) -> Array1<f64> {
+        self.filtered.predicted_states.slice(s![self.start.., 0]).to_owned()
+    }
+
+    fn residuals(&self) -> Array1<f64> {
+        &self.history.slice(s![self.start..]) - &self.fitted_values()
+    }
+
+    fn smoothed_states(&self) -> Result<Array2<f64>, String> {
+        Ok(self.model.smooth_filtered(&self.filtered)?.states)
+    }
+}
+
+/// Implements `Forecaster` for a model with `spec()` and a
+/// `fit: Option<StructuralFit>` field
+macro_rules! structural_forecaster {
+    ($model:ty) => {
+        impl Forecaster for $model {
+            /// Fit by maximum likelihood. NaN values are treated as missing.
+            fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+                self.fit = Some(self.spec().fit(data)?);
+                Ok(())
+            }
+
+            fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.forecast(horizon)?.0),
+                    None => Err("Model must be fitted before forecasting".to_string()),
+                }
+            }
+
+            /// One-step predictions after the diffuse start-up steps
+            fn fitted_values(&self) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.fitted_values()),
+                    None => Err("Model must be fitted before computing fitted values".to_string()),
+                }
+            }
+
+            /// One-step errors, NaN where the data is missing
+            fn residuals(&self) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.residuals()),
+                    None => Err("Model must be fitted before computing residuals".to_string()),
+                }
+            }
+
+            fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.forecast(horizon)?.1),
+                    None => Err("Model must be fitted before computing forecast variance".to_string()),
+                }
+            }
+        }
+    };
+}
+
+/// Random walk observed with noise: `y_t = mu_t + e_t`, `mu_{t+1} = mu_t + eta_t`.
+///
+/// Both variances are estimated by maximum likelihood. The smoothed level is a
+/// principled denoised version of the series and also fills missing values.
+pub struct LocalLevel {
+    fit: Option<StructuralFit>,
+}
+
+impl LocalLevel {
+    /// Create a new local-level model
+    pub fn new() -> Self {
+        LocalLevel { fit: None }
+    }
+
+    fn spec(&self) -> StructuralSpec {
+        StructuralSpec { trend: false }
+    }
+
+    /// Fitted (observation, level) variances, `None` before `fit`
+    pub fn variances(&self) -> Option<(f64, f64)> {
+        self.fit.as_ref().map(|fit| (fit.variances[0], fit.variances[1]))
+    }
+
+    /// Maximised log-likelihood
+    pub fn log_likelihood(&self) -> Option<f64> {
+        self.fit.as_ref().map(|fit| fit.filtered.log_likelihood)
+    }
+
+    /// Smoothed level at every time step of the training series
+    pub fn smoothed_level(&self) -> Result<Array1<f64>, String> {
+        match &self.fit {
+            Some(fit) => Ok(fit.smoothed_states()?.column(0).to_owned()),
+            None => Err("Model must be fitted before smoothing".to_string()),
+        }
+    }
+}
+
+impl Default for LocalLevel {
+    fn default() -> Self {
+        LocalLevel::new()
+    }
+}
+
+structural_forecaster!(LocalLevel);
+
+/// Local level with a stochastic slope:
+/// `y_t = mu_t + e_t`, `mu_{t+1} = mu_t + nu_t + eta_t`, `nu_{t+1} = nu_t + zeta_t`.
+pub struct LocalLinearTrend {
+    fit: Option<StructuralFit>,
+}
+
+impl LocalLinearTrend {
+    /// Create a new local linear trend model
+    pub fn new() -> Self {
+        LocalLinearTrend { fit: None }
+    }
+
+    fn spec(&self) -> StructuralSpec {
+        StructuralSpec { trend: true }
+    }
+
+    /// Fitted (observation, level, slope) variances, `None` before `fit`
+    pub fn variances(&self) -> Option<(f64, f64, f64)> {
+        self.fit
+            .as_ref()
+            .map(|fit| (fit.variances[0], fit.variances[1], fit.variances[2]))
+    }
+
+    /// Maximised log-likelihood
+    pub fn log_likelihood(&self) -> Option<f64> {
+        self.fit.as_ref().map(|fit| fit.filtered.log_likelihood)
+    }
+
+    /// Smoothed level and slope, one row per time step
+    pub fn smoothed_states(&self) -> Result<Array2<f64>, String> {
+        match &self.fit {
+            Some(fit) => fit.smoothed_states(),
+            None => Err("Model must be fitted before smoothing".to_string()),
+        }
+    }
+}
+
+impl Default for LocalLinearTrend {
+    fn default() -> Self {
+        LocalLinearTrend::new()
+    }
+}
+
+structural_forecaster!(LocalLinearTrend);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::normal_noise;
+
+    fn local_level(obs_var: f64, level_var: f64) -> StateSpaceModel {
+        StateSpaceModel::new(
+            Array2::eye(1),
+            Array2::eye(1),
+            Array2::from_elem((1, 1), level_var),
+            Array2::from_elem((1, 1), obs_var),
+            Array1::zeros(1),
+            Array2::eye(1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_by_hand() {
+        let model = local_level(1.0, 1.0);
+        let output = model.filter(&Array2::from_shape_vec((2, 1), vec![1.0, 2.0]).unwrap()).unwrap();
+
+        // t = 0: F = 2, K = 0.5
+        assert!((output.filtered_states[[0, 0]] - 0.5).abs() < 1e-12);
+        assert!((output.filtered_covs[0][[0, 0]] - 0.5).abs() < 1e-12);
+        // t = 1: P = 1.5, F = 2.5, K = 0.6
+        assert!((output.predicted_covs[1][[0, 0]] - 1.5).abs() < 1e-12);
+        assert!((output.filtered_states[[1, 0]] - (0.5 + 0.6 * 1.5)).abs() < 1e-12);
+
+        let expected = -0.5 * (2.0 * (2.0 * std::f64::consts::PI).ln() + 2f64.ln() + 0.5 + 2.5f64.ln() + 2.25 / 2.5);
+        assert!((output.log_likelihood - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_missing_values_skip_update() {
+        let model = local_level(1.0, 0.1);
+        let data = Array2::from_shape_vec((4, 1), vec![1.0, f64::NAN, f64::NAN, 2.0]).unwrap();
+        let output = model.filter(&data).unwrap();
+
+        assert!(output.innovations[[1, 0]].is_nan());
+        assert_eq!(output.filtered_states[[1, 0]], output.filtered_states[[0, 0]]);
+        assert!(output.filtered_covs[2][[0, 0]] > output.filtered_covs[1][[0, 0]]);
+
+        // The smoother interpolates the gap using both neighbours
+        let smoothed = model.smooth(&data).unwrap();
+        let gap = smoothed.states[[1, 0]];
+        assert!(gap > output.filtered_states[[0, 0]] && gap < output.filtered_states[[3, 0]]);
+        assert!(smoothed.covs[1][[0, 0]] < output.filtered_covs[1][[0, 0]]);
+    }
+
+    #[test]
+    fn test_smoother_ends_at_filter() {
+        let model = local_level(0.5, 0.2);
+        let data = Array2::from_shape_vec((5, 1), vec![1.0, 1.4, 0.9, 1.8, 1.6]).unwrap();
+        let filtered = model.filter(&data).unwrap();
+        let smoothed = model.smooth_filtered(&filtered).unwrap();
+
+        assert_eq!(smoothed.states.row(4), filtered.filtered_states.row(4));
+        for t in 0..5 {
+            assert!(smoothed.covs[t][[0, 0]] <= filtered.filtered_covs[t][[0, 0]] + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bivariate_observation() {
+        // Two sensors reading the same level, the noisier one counts less
+        let model = StateSpaceModel::new(
+            Array2::eye(1),
+            Array2::from_shape_vec((2, 1), vec![1.0, 1.0]).unwrap(),
+            Array2::from_elem((1, 1), 0.1),
+            Array2::from_diag(&Array1::from(vec![1.0, 4.0])),
+            Array1::zeros(1),
+            Array2::from_elem((1, 1), 1e7),
+        )
+        .unwrap();
+        let data = Array2::from_shape_vec((1, 2), vec![10.0, 20.0]).unwrap();
+        let output = model.filter(&data).unwrap();
+
+        // Precision-weighted average of the two readings
+        assert!((output.filtered_states[[0, 0]] - (10.0 + 20.0 / 4.0) / 1.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_local_level_mle() {
+        let e = normal_noise(400, 3);
+        let eta = normal_noise(400, 11);
+        let mut level = 20.0;
+        let data: Array1<f64> = (0..400)
+            .map(|t| {
+                level += 0.5 * eta[t];
+                level + 2.0 * e[t]
+            })
+            .collect();
+
+        let mut model = LocalLevel::new();
+        model.fit(&data).unwrap();
+        let (obs_var, level_var) = model.variances().unwrap();
+        assert!(obs_var > 2.5 && obs_var < 6.0, "obs_var = {}", obs_var);
+        assert!(level_var > 0.1 && level_var < 0.5, "level_var = {}", level_var);
+
+        let variance = model.forecast_variance(3).unwrap();
+        assert!((variance[1] - variance[0] - level_var).abs() < 1e-8);
+        assert_eq!(model.smoothed_level().unwrap().len(), 400);
+    }
+
+    #[test]
+    fn test_local_linear_trend_extrapolates_line() {
+        let data: Array1<f64> = (0..40).map(|t| 3.0 + 0.5 * t as f64).collect();
+        let mut model = LocalLinearTrend::new();
+        model.fit(&data).unwrap();
+
+        let forecasts = model.predict(3).unwrap();
+        for h in 0..3 {
+            assert!((forecasts[h] - (3.0 + 0.5 * (40 + h) as f64)).abs() < 1e-3);
+        }
+
+        let fitted = model.fitted_values().unwrap();
+        let residuals = model.residuals().unwrap();
+        assert_eq!(fitted.len(), 38);
+        assert!((fitted[10] + residuals[10] - data[12]).abs() < 1e-10);
+
+        // Leading gaps delay the end of the diffuse start-up
+        let mut gappy = data.clone();
+        gappy[0] = f64::NAN;
+        gappy[1] = f64::NAN;
+        gappy[3] = f64::NAN;
+        model.fit(&gappy).unwrap();
+        let fitted = model.fitted_values().unwrap();
+        let residuals = model.residuals().unwrap();
+        assert_eq!(fitted.len(), 35);
+        assert!(residuals[0].is_finite());
+        assert!(residuals[1].abs() < 1e-3);
+        assert!((fitted[1] + residuals[1] - data[6]).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_dimension_mismatch_rejected() {
+        let result = StateSpaceModel::new(
+            Array2::eye(2),
+            Array2::eye(1),
+            Array2::eye(2),
+            Array2::eye(1),
+            Array1::zeros(2),
+            Array2::eye(2),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -6,7 +6,32 @@ pub mod tournament;
 pub mod crossover_mutation;
 pub mod selection;
 pub mod arima;
+pub mod diagnostics;
+pub mod forecaster;
+pub mod smoothing;
+pub mod optimize;
+pub mod backtest;
+pub mod online;
+pub mod transform;
+pub mod stationarity;
+pub mod decomposition;
+pub mod kalman;
+pub mod anomaly;
+pub mod changepoint;
+pub mod var;
+pub mod spectral;
+pub mod interpolation;
+pub mod multivariate_imputation;
+pub mod rolling;
+pub mod filters;
+pub mod statistics;
+pub mod hypothesis;
+pub mod correlation;
+pub mod regression;
 pub mod satisfaccion;
+#[cfg(test)]
+mod test_utils;
 // Re-export main components for easier access
 pub use genetic_optimizer::GeneticOptimizer;
-pub use data_imputation::{linear_interpolation, simple_exponential_smoothing, median};
+pub use forecaster::{Forecaster, ModelConfig};
+pub use data_imputation::{linear_interpolation, simple_exponential_smoothing, median, impute, impute_nan, evaluate_imputation, ImputationStrategy};
@@ -0,0 +1,260 @@
+// src/ml/multivariate_imputation.rs
+use ndarray::{Array1, Array2, Axis};
+use ndarray_linalg::Solve;
+
+/// Ridge added to the diagonal of the MICE regressions, keeps collinear sensors solvable
+const RIDGE: f64 = 1e-8;
+
+/// A completed table together with the cells that were filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultivariateImputed {
+    pub values: Array2<f64>,
+    pub imputed: Vec<(usize, usize)>,  // (row, column) of every missing cell, row by row
+}
+
+/// Mean and standard deviation of the observed values of every column.
+/// Infinite values are rejected, they would poison every distance and regression.
+fn column_moments(data: &Array2<f64>) -> Result<(Vec<f64>, Vec<f64>), String> {
+    let mut means = Vec::with_capacity(data.ncols());
+    let mut sds = Vec::with_capacity(data.ncols());
+    for (j, column) in data.axis_iter(Axis(1)).enumerate() {
+        let observed: Vec<f64> = column.iter().copied().filter(|v| !v.is_nan()).collect();
+        if observed.is_empty() {
+            return Err(format!("Column {} has no observed values", j));
+        }
+        if observed.iter().any(|v| v.is_infinite()) {
+            return Err(format!("Column {} contains infinite values", j));
+        }
+        let mean = observed.iter().sum::<f64>() / observed.len() as f64;
+        let var = observed.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / observed.len() as f64;
+        means.push(mean);
+        sds.push(if var > 0.0 { var.sqrt() } else { 1.0 });
+    }
+    Ok((means, sds))
+}
+
+fn missing_cells(data: &Array2<f64>) -> Vec<(usize, usize)> {
+    data.indexed_iter()
+        .filter(|(_, v)| v.is_nan())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Intercept followed by every column of row `i` except `target`.
+fn design_row(values: &Array2<f64>, i: usize, target: usize) -> Array1<f64> {
+    let mut x = Array1::<f64>::ones(values.ncols());
+    for (c, p) in (0..values.ncols()).filter(|&c| c != target).enumerate() {
+        x[c + 1] = values[[i, p]];
+    }
+    x
+}
+
+/// Fill missing cells with the mean of the `k` most similar rows.
+///
+/// Columns are standardized before measuring distances so sensors on
+/// different scales weigh the same. The distance between two rows uses only
+/// the columns observed in both, scaled up by the share of columns that were
+/// skipped. Rows with no column in common with any donor fall back to the
+/// column mean.
+///
+/// # Arguments
+///
+/// * `data` - Table with one observation per row, NaN marks a missing cell and infinite values are rejected
+/// * `k` - Number of neighbours averaged for every missing cell
+///
+/// # Returns
+///
+/// The completed table and the imputed cells
+pub fn knn_impute(data: &Array2<f64>, k: usize) -> Result<MultivariateImputed, String> {
+    if k == 0 {
+        return Err("k must be at least 1".to_string());
+    }
+    let (means, sds) = column_moments(data)?;
+    let imputed = missing_cells(data);
+    let mut values = data.clone();
+    let cols = data.ncols();
+
+    let mut standardized = data.clone();
+    for mut row in standardized.axis_iter_mut(Axis(0)) {
+        for j in 0..cols {
+            row[j] = (row[j] - means[j]) / sds[j];
+        }
+    }
+
+    let incomplete: Vec<usize> = (0..data.nrows())
+        .filter(|&i| data.row(i).iter().any(|v| v.is_nan()))
+        .collect();
+
+    for &i in &incomplete {
+        let row = standardized.row(i);
+        let distances: Vec<Option<f64>> = standardized
+            .axis_iter(Axis(0))
+            .enumerate()
+            .map(|(r, other)| {
+                if r == i {
+                    return None;
+                }
+                let mut sum = 0.0;
+                let mut shared = 0;
+                for j in 0..cols {
+                    if !row[j].is_nan() && !other[j].is_nan() {
+                        sum += (row[j] - other[j]).powi(2);
+                        shared += 1;
+                    }
+                }
+                if shared == 0 {
+                    None
+                } else {
+                    Some((sum * cols as f64 / shared as f64).sqrt())
+                }
+            })
+            .collect();
+
+        for j in 0..cols {
+            if !data[[i, j]].is_nan() {
+                continue;
+            }
+            let mut donors: Vec<(f64, f64)> = distances
+                .iter()
+                .enumerate()
+                .filter_map(|(r, d)| d.filter(|_| !data[[r, j]].is_nan()).map(|d| (d, data[[r, j]])))
+                .collect();
+            values[[i, j]] = if donors.is_empty() {
+                means[j]
+            } else {
+                donors.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                let nearest = &donors[..k.min(donors.len())];
+                nearest.iter().map(|(_, v)| v).sum::<f64>() / nearest.len() as f64
+            };
+        }
+    }
+
+    Ok(MultivariateImputed { values, imputed })
+}
+
+/// Fill missing cells by chained regressions (MICE-style iterative imputation).
+///
+/// Missing cells start at their column mean. Every pass regresses each
+/// incomplete column on all the other columns, using the rows where it is
+/// observed, and replaces its missing cells with the predictions. Passes
+/// repeat until no imputed cell moves more than `tol` or `max_iter` is hit.
+///
+/// # Arguments
+///
+/// * `data` - Table with one observation per row, NaN marks a missing cell and infinite values are rejected
+/// * `max_iter` - Maximum number of passes over the columns
+/// * `tol` - Largest change of an imputed cell, relative to its column spread, that counts as converged
+///
+/// # Returns
+///
+/// The completed table and the imputed cells
+pub fn mice_impute(data: &Array2<f64>, max_iter: usize, tol: f64) -> Result<MultivariateImputed, String> {
+    let (means, sds) = column_moments(data)?;
+    let imputed = missing_cells(data);
+    let mut values = data.clone();
+    for &(i, j) in &imputed {
+        values[[i, j]] = means[j];
+    }
+    let cols = data.ncols();
+    if cols < 2 {
+        return Ok(MultivariateImputed { values, imputed });
+    }
+
+    for _ in 0..max_iter {
+        let mut largest_change: f64 = 0.0;
+        for j in 0..cols {
+            let column = data.column(j);
+            if !column.iter().any(|v| v.is_nan()) {
+                continue;
+            }
+            let observed: Vec<usize> = (0..data.nrows()).filter(|&i| !column[i].is_nan()).collect();
+            let mut x_t_x = Array2::<f64>::zeros((cols, cols));
+            let mut x_t_y = Array1::<f64>::zeros(cols);
+            for &i in &observed {
+                let x = design_row(&values, i, j);
+                for a in 0..cols {
+                    x_t_y[a] += x[a] * column[i];
+                    for b in 0..cols {
+                        x_t_x[[a, b]] += x[a] * x[b];
+                    }
+                }
+            }
+            for a in 1..cols {
+                x_t_x[[a, a]] += RIDGE * (1.0 + x_t_x[[a, a]]);
+            }
+            let beta = x_t_x
+                .solve(&x_t_y)
+                .map_err(|_| format!("Regression for column {} is singular", j))?;
+
+            for i in (0..data.nrows()).filter(|&i| column[i].is_nan()) {
+                let prediction = design_row(&values, i, j).dot(&beta);
+                largest_change = largest_change.max((prediction - values[[i, j]]).abs() / sds[j]);
+                values[[i, j]] = prediction;
+            }
+        }
+        if largest_change <= tol {
+            break;
+        }
+    }
+
+    Ok(MultivariateImputed { values, imputed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor_table() -> Array2<f64> {
+        // Humidity falls as temperature rises, dew point follows both
+        let mut data = Array2::<f64>::zeros((40, 3));
+        for i in 0..40 {
+            let temperature = 18.0 + 0.25 * i as f64;
+            let humidity = 80.0 - 1.5 * i as f64;
+            data[[i, 0]] = temperature;
+            data[[i, 1]] = humidity;
+            data[[i, 2]] = 0.5 * temperature + 0.1 * humidity + 2.0;
+        }
+        data
+    }
+
+    #[test]
+    fn test_knn_impute() {
+        let truth = sensor_table();
+        let mut data = truth.clone();
+        data[[10, 1]] = f64::NAN;
+        data[[25, 0]] = f64::NAN;
+        data[[25, 2]] = f64::NAN;
+
+        let result = knn_impute(&data, 2).unwrap();
+        assert_eq!(result.imputed, vec![(10, 1), (25, 0), (25, 2)]);
+        // The two nearest rows are the neighbours in time, whose mean is the true value
+        assert!((result.values[[10, 1]] - truth[[10, 1]]).abs() < 1e-9);
+        assert!((result.values[[25, 0]] - truth[[25, 0]]).abs() < 1e-9);
+        assert!((result.values[[25, 2]] - truth[[25, 2]]).abs() < 1e-9);
+        assert_eq!(result.values[[0, 0]], truth[[0, 0]]);
+
+        assert!(knn_impute(&data, 0).is_err());
+        data[[5, 2]] = f64::INFINITY;
+        assert!(knn_impute(&data, 2).is_err());
+        assert!(mice_impute(&data, 10, 1e-6).is_err());
+    }
+
+    #[test]
+    fn test_mice_impute() {
+        let truth = sensor_table();
+        let mut data = truth.clone();
+        for &(i, j) in &[(3, 2), (12, 2), (20, 1), (33, 0), (34, 2)] {
+            data[[i, j]] = f64::NAN;
+        }
+
+        let result = mice_impute(&data, 50, 1e-10).unwrap();
+        assert_eq!(result.imputed.len(), 5);
+        for &(i, j) in &result.imputed {
+            assert!((result.values[[i, j]] - truth[[i, j]]).abs() < 1e-3);
+        }
+
+        let mut empty = truth.clone();
+        empty.column_mut(1).fill(f64::NAN);
+        assert!(mice_impute(&empty, 10, 1e-6).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+// src/ml/online.rs
+use ndarray::Array1;
+use std::collections::VecDeque;
+
+use crate::ML::arima::ARIMA;
+use crate::ML::forecaster::Forecaster;
+
+/// Streaming wrapper around a fitted ARIMA model.
+///
+/// Each new reading updates the model state in O(d + p + q) and returns the
+/// next `horizon` forecasts. When `refit_every` is set, the model is refitted
+/// on the last `window` readings every `refit_every` observations, once that
+/// many readings have been seen. A failed refit keeps the current model and
+/// does not interrupt the stream, see `refit_error`.
+pub struct OnlineARIMA {
+    pub horizon: usize,
+    pub refit_every: Option<usize>,
+    pub window: usize,
+    model: ARIMA,
+    recent: VecDeque<f64>,  // Last `window` readings, used for refits
+    since_refit: usize,
+    refit_error: Option<String>,  // Why the last refit failed, cleared by the next successful one
+}
+
+impl OnlineARIMA {
+    /// Wrap a fitted model (from `fit` or `ARIMA::load`) without periodic refits.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - A fitted ARIMA model
+    /// * `horizon` - Number of steps forecast after every reading
+    pub fn new(model: ARIMA, horizon: usize) -> Result<Self, String> {
+        if !model.is_fitted() {
+            return Err("Model must be fitted before streaming".to_string());
+        }
+        Ok(OnlineARIMA {
+            horizon,
+            refit_every: None,
+            window: 0,
+            model,
+            recent: VecDeque::new(),
+            since_refit: 0,
+            refit_error: None,
+        })
+    }
+
+    /// Refit every `every` readings on the last `window` readings
+    pub fn with_refit(mut self, every: usize, window: usize) -> Result<Self, String> {
+        if every == 0 || window == 0 {
+            return Err("Refit interval and window must be positive".to_string());
+        }
+        self.refit_every = Some(every);
+        self.window = window;
+        Ok(self)
+    }
+
+    /// The current model
+    pub fn model(&self) -> &ARIMA {
+        &self.model
+    }
+
+    /// Why the last scheduled refit failed, `None` once a refit succeeds.
+    ///
+    /// The current model keeps forecasting and the fit is retried at the
+    /// next scheduled refit.
+    pub fn refit_error(&self) -> Option<&str> {
+        self.refit_error.as_deref()
+    }
+
+    /// Feed one reading and return the forecasts for the next `horizon` steps.
+    /// NaN and infinite readings are rejected and leave the stream unchanged.
+    pub fn push(&mut self, value: f64) -> Result<Array1<f64>, String> {
+        self.model.update(value)?;
+
+        if let Some(every) = self.refit_every {
+            self.recent.push_back(value);
+            while self.recent.len() > self.window {
+                self.recent.pop_front();
+            }
+            self.since_refit += 1;
+
+            if self.since_refit >= every && self.recent.len() == self.window {
+                self.refit();
+            }
+        }
+
+        self.forecast()
+    }
+
+    /// Forecasts from the latest reading
+    pub fn forecast(&self) -> Result<Array1<f64>, String> {
+        self.model.predict(self.horizon)
+    }
+
+    /// Refit a fresh model on the recent window, keeping the current one on
+    /// failure until the next scheduled refit
+    fn refit(&mut self) {
+        self.since_refit = 0;
+        let (p, d, q) = self.model.order();
+        let mut refitted = ARIMA::new(p, d, q);
+        match refitted.fit(&Array1::from(self.recent.iter().copied().collect::<Vec<f64>>())) {
+            Ok(()) => {
+                self.model = refitted;
+                self.refit_error = None;
+            }
+            Err(e) => self.refit_error = Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|t| {
+                let t = t as f64;
+                25.0 + 4.0 * (0.3 * t).sin() + 0.3 * (1.7 * t).cos() + 0.2 * (3.1 * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_push_tracks_batch_forecast() {
+        let data = series(50);
+        let mut model = ARIMA::new(1, 1, 1);
+        model.fit(&Array1::from(data[..40].to_vec())).unwrap();
+        let reference = ARIMA::from_json(&model.to_json().unwrap()).unwrap();
+
+        let mut online = OnlineARIMA::new(model, 2).unwrap();
+        let mut forecasts = Array1::zeros(0);
+        for &value in &data[40..] {
+            forecasts = online.push(value).unwrap();
+        }
+
+        // Same parameters applied to the whole series in one go
+        let mut batch = reference;
+        for &value in &data[40..] {
+            batch.update(value).unwrap();
+        }
+        assert_eq!(forecasts, batch.predict(2).unwrap());
+    }
+
+    #[test]
+    fn test_periodic_refit() {
+        let data = series(60);
+        let mut model = ARIMA::new(1, 0, 0);
+        model.fit(&Array1::from(data[..30].to_vec())).unwrap();
+
+        let mut online = OnlineARIMA::new(model, 3).unwrap().with_refit(10, 25).unwrap();
+        let mut forecasts = Array1::zeros(0);
+        for &value in &data[30..60] {
+            forecasts = online.push(value).unwrap();
+        }
+
+        // The window fills after 25 readings, which triggers the only refit;
+        // the last 5 readings are then applied as updates
+        let mut expected = ARIMA::new(1, 0, 0);
+        expected.fit(&Array1::from(data[30..55].to_vec())).unwrap();
+        for &value in &data[55..60] {
+            expected.update(value).unwrap();
+        }
+        let expected = expected.predict(3).unwrap();
+        assert!(online.refit_error().is_none());
+        for (a, b) in forecasts.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_failed_refit_keeps_model() {
+        let data = series(40);
+        let mut model = ARIMA::new(1, 0, 0);
+        model.fit(&Array1::from(data[..30].to_vec())).unwrap();
+        let mut reference = ARIMA::from_json(&model.to_json().unwrap()).unwrap();
+
+        // Two readings are too few to fit an AR(1), so every refit fails
+        let mut online = OnlineARIMA::new(model, 2).unwrap().with_refit(1, 2).unwrap();
+        for (i, &value) in data[30..].iter().enumerate() {
+            let forecasts = online.push(value).unwrap();
+            reference.update(value).unwrap();
+            assert_eq!(forecasts, reference.predict(2).unwrap());
+            // The first refit runs once the window holds two readings
+            assert_eq!(online.refit_error().is_some(), i >= 1);
+        }
+    }
+
+    #[test]
+    fn test_unfitted_model_rejected() {
+        assert!(OnlineARIMA::new(ARIMA::new(1, 0, 0), 1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_refit_schedule_rejected() {
+        let mut model = ARIMA::new(1, 0, 0);
+        model.fit(&Array1::from(series(30))).unwrap();
+        let online = OnlineARIMA::new(model, 1).unwrap();
+        assert!(online.with_refit(0, 10).is_err());
+    }
+}
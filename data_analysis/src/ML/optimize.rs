@@ -0,0 +1,164 @@
+// src/ml/optimize.rs
+
+/// Minimise a function with the Nelder-Mead simplex method.
+///
+/// Derivative free, which suits the small likelihood and SSE surfaces of the
+/// time-series models. Constrained parameters should be mapped onto the real
+/// line (for example with `bounded`) before calling this.
+///
+/// # Arguments
+///
+/// * `f` - Objective function to minimise
+/// * `initial` - Starting point
+/// * `step` - Size of the initial simplex along each coordinate
+/// * `max_iter` - Maximum number of iterations
+/// * `tol` - Stop when the objective values of the simplex differ by less than this
+///
+/// # Returns
+///
+/// The best point found and its objective value
+pub fn nelder_mead<F>(f: F, initial: &[f64], step: f64, max_iter: usize, tol: f64) -> (Vec<f64>, f64)
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let n = initial.len();
+    let evaluate = |x: &[f64]| {
+        let value = f(x);
+        if value.is_nan() { f64::INFINITY } else { value }
+    };
+
+    // Initial simplex: the starting point plus one step along each axis
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((initial.to_vec(), evaluate(initial)));
+    for i in 0..n {
+        let mut vertex = initial.to_vec();
+        vertex[i] += step;
+        let value = evaluate(&vertex);
+        simplex.push((vertex, value));
+    }
+
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let best = simplex[0].1;
+        let worst = simplex[n].1;
+        if (worst - best).abs() <= tol * (best.abs() + tol) {
+            break;
+        }
+
+        // Centroid of every vertex except the worst
+        let mut centroid = vec![0.0; n];
+        for (vertex, _) in &simplex[..n] {
+            for j in 0..n {
+                centroid[j] += vertex[j] / n as f64;
+            }
+        }
+        let towards = |coef: f64| -> Vec<f64> {
+            (0..n).map(|j| centroid[j] + coef * (simplex[n].0[j] - centroid[j])).collect()
+        };
+
+        let reflected = towards(-1.0);
+        let reflected_value = evaluate(&reflected);
+
+        if reflected_value < best {
+            let expanded = towards(-2.0);
+            let expanded_value = evaluate(&expanded);
+            simplex[n] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = if reflected_value < worst { towards(-0.5) } else { towards(0.5) };
+            let contracted_value = evaluate(&contracted);
+            if contracted_value < worst.min(reflected_value) {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                // Shrink every vertex towards the best one
+                let best_vertex = simplex[0].0.clone();
+                for (vertex, value) in simplex.iter_mut().skip(1) {
+                    for j in 0..n {
+                        vertex[j] = best_vertex[j] + 0.5 * (vertex[j] - best_vertex[j]);
+                    }
+                    *value = evaluate(vertex);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    simplex.swap_remove(0)
+}
+
+/// Minimise a unimodal function of one variable on `[low, high]` by golden-section search.
+///
+/// # Returns
+///
+/// The minimising point, within `tol` of the true minimum
+pub fn golden_section<F>(f: F, low: f64, high: f64, tol: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (low, high);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut fc, mut fd) = (f(c), f(d));
+
+    while (b - a).abs() > tol {
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+    }
+
+    (a + b) / 2.0
+}
+
+/// Map an unconstrained value onto the open interval `(low, high)`.
+pub fn bounded(x: f64, low: f64, high: f64) -> f64 {
+    low + (high - low) / (1.0 + (-x).exp())
+}
+
+/// Inverse of `bounded`: map a value in `(low, high)` onto the real line.
+pub fn unbounded(value: f64, low: f64, high: f64) -> f64 {
+    let p = (value - low) / (high - low);
+    (p / (1.0 - p)).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nelder_mead_rosenbrock() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+        let (best, value) = nelder_mead(rosenbrock, &[-1.2, 1.0], 0.5, 5000, 1e-14);
+        assert!((best[0] - 1.0).abs() < 1e-3);
+        assert!((best[1] - 1.0).abs() < 1e-3);
+        assert!(value < 1e-6);
+    }
+
+    #[test]
+    fn test_bounded_roundtrip() {
+        let value = bounded(unbounded(0.3, 0.0, 1.0), 0.0, 1.0);
+        assert!((value - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_golden_section() {
+        let minimum = golden_section(|x| (x - 0.7).powi(2) + 1.0, -1.0, 2.0, 1e-8);
+        assert!((minimum - 0.7).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,552 @@
+// src/ml/regression.rs
+use ndarray::{s, Array1, Array2, Axis};
+use ndarray_linalg::Inverse;
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+/// Iteration cap of the lasso coordinate descent
+const LASSO_MAX_ITER: usize = 10_000;
+/// Largest coefficient change that stops the lasso coordinate descent
+const LASSO_TOLERANCE: f64 = 1e-10;
+
+/// Penalty on the slopes; the intercept is never penalised
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Regularization {
+    /// Ordinary least squares
+    None,
+    /// Minimises `||y - Xb||² + alpha ||b||²`
+    Ridge(f64),
+    /// Minimises `||y - Xb||² / (2n) + alpha ||b||₁`, driving weak slopes to exactly zero
+    Lasso(f64),
+}
+
+/// An estimated coefficient with its sampling uncertainty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coefficient {
+    pub estimate: f64,
+    pub std_error: f64,  // NaN for lasso, which has no closed-form standard errors
+    pub t_value: f64,
+    pub p_value: f64,  // Two-sided, against a zero coefficient
+}
+
+/// Fit statistics of a regression
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionSummary {
+    pub intercept: Option<Coefficient>,
+    pub coefficients: Vec<Coefficient>,  // One per column of the design matrix
+    pub r_squared: f64,
+    pub adj_r_squared: f64,
+    pub sigma: f64,  // Residual standard error
+    pub df_resid: f64,  // Observations minus effective number of parameters
+    pub residuals: Array1<f64>,
+}
+
+/// Point predictions with the bounds of their prediction intervals
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictionIntervals {
+    pub mean: Array1<f64>,
+    pub lower: Array1<f64>,
+    pub upper: Array1<f64>,
+}
+
+/// Everything kept from a fit
+#[derive(Debug, Clone)]
+struct Fitted {
+    beta: Array1<f64>,  // Intercept first when fitted, then the slopes
+    covariance: Option<Array2<f64>>,  // Of the standardised coefficients divided by sigma², unavailable for lasso
+    transform: Array2<f64>,  // Maps standardised coefficients to beta, and design rows to standardised rows
+    sigma2: f64,
+    df_resid: f64,
+    r_squared: f64,
+    adj_r_squared: f64,
+    residuals: Array1<f64>,
+}
+
+/// Linear regression `y = b0 + X b + e` by least squares, optionally with a
+/// ridge or lasso penalty.
+///
+/// Standard errors and prediction intervals assume independent normal
+/// errors with constant variance. With a ridge penalty they come from the
+/// sandwich covariance and the effective degrees of freedom `n - tr(H)`,
+/// and ignore the shrinkage bias.
+#[derive(Debug, Clone)]
+pub struct LinearRegression {
+    pub fit_intercept: bool,
+    pub regularization: Regularization,
+    fitted: Option<Fitted>,
+}
+
+impl Default for LinearRegression {
+    fn default() -> Self {
+        LinearRegression::new()
+    }
+}
+
+impl LinearRegression {
+    /// Ordinary least squares with an intercept
+    pub fn new() -> Self {
+        LinearRegression {
+            fit_intercept: true,
+            regularization: Regularization::None,
+            fitted: None,
+        }
+    }
+
+    pub fn ridge(alpha: f64) -> Self {
+        LinearRegression {
+            regularization: Regularization::Ridge(alpha),
+            ..LinearRegression::new()
+        }
+    }
+
+    pub fn lasso(alpha: f64) -> Self {
+        LinearRegression {
+            regularization: Regularization::Lasso(alpha),
+            ..LinearRegression::new()
+        }
+    }
+
+    pub fn is_fitted(&self) -> bool {
+        self.fitted.is_some()
+    }
+
+    /// Design matrix with a leading column of ones when the intercept is fitted
+    fn design(&self, x: &Array2<f64>) -> Array2<f64> {
+        if !self.fit_intercept {
+            return x.clone();
+        }
+        let mut design = Array2::<f64>::ones((x.nrows(), x.ncols() + 1));
+        design.slice_mut(s![.., 1..]).assign(x);
+        design
+    }
+
+    /// Centres and scales the slope columns of the design so the normal
+    /// equations stay well conditioned, e.g. for cubic calibration curves of
+    /// raw ADC counts. Returns `S` such that `design · S` is the standardised
+    /// design and `beta = S · gamma` for its coefficients `gamma`.
+    fn standardization(&self, design: &Array2<f64>) -> Array2<f64> {
+        let k = design.ncols();
+        let first_slope = self.fit_intercept as usize;
+        let n = design.nrows() as f64;
+        let mut transform = Array2::<f64>::eye(k);
+        for j in first_slope..k {
+            let column = design.column(j);
+            // Without an intercept the columns can only be scaled, not centred
+            let shift = if self.fit_intercept { column.sum() / n } else { 0.0 };
+            let spread = (column.mapv(|v| (v - shift).powi(2)).sum() / n).sqrt();
+            let scale = if spread > 0.0 { spread } else { 1.0 };
+            transform[[j, j]] = 1.0 / scale;
+            if self.fit_intercept {
+                transform[[0, j]] = -shift / scale;
+            }
+        }
+        transform
+    }
+
+    /// Fit the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - One row per observation and one column per predictor, without a constant column
+    /// * `y` - The response, one value per row of `x`
+    ///
+    /// # Returns
+    ///
+    /// Nothing; the coefficients and statistics are read with `summary`
+    pub fn fit(&mut self, x: &Array2<f64>, y: &Array1<f64>) -> Result<(), String> {
+        if x.nrows() != y.len() {
+            return Err("x and y must have the same number of rows".to_string());
+        }
+        if x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+            return Err("Data must be finite, impute missing values first".to_string());
+        }
+        let design = self.design(x);
+        let n = design.nrows();
+        let k = design.ncols();
+        let first_slope = self.fit_intercept as usize;
+
+        let mut transform = Array2::<f64>::eye(k);
+        let (beta, covariance, parameters) = match self.regularization {
+            Regularization::None | Regularization::Ridge(_) => {
+                let alpha = match self.regularization {
+                    Regularization::Ridge(alpha) if alpha < 0.0 => {
+                        return Err("Ridge penalty must be non-negative".to_string())
+                    }
+                    Regularization::Ridge(alpha) => alpha,
+                    _ => 0.0,
+                };
+                transform = self.standardization(&design);
+                let standardized = design.dot(&transform);
+                let gram = standardized.t().dot(&standardized);
+                let mut penalised = gram.clone();
+                for j in first_slope..k {
+                    // The penalty is on the original slopes, beta_j = gamma_j * S_jj
+                    penalised[[j, j]] += alpha * transform[[j, j]].powi(2);
+                }
+                let inverse = penalised
+                    .inv()
+                    .map_err(|_| "Design matrix is singular, check for collinear predictors".to_string())?;
+                let gamma = inverse.dot(&standardized.t().dot(y));
+                let covariance = inverse.dot(&gram).dot(&inverse);
+                let parameters = (0..k).map(|j| inverse.row(j).dot(&gram.column(j))).sum::<f64>();
+                (transform.dot(&gamma), Some(covariance), parameters)
+            }
+            Regularization::Lasso(alpha) => {
+                if alpha < 0.0 {
+                    return Err("Lasso penalty must be non-negative".to_string());
+                }
+                let beta = self.lasso_coordinate_descent(x, y, alpha)?;
+                let nonzero = beta.iter().skip(first_slope).filter(|b| **b != 0.0).count();
+                (beta, None, (nonzero + first_slope) as f64)
+            }
+        };
+
+        let df_resid = n as f64 - parameters;
+        if df_resid <= 0.0 {
+            return Err("Not enough observations for the number of predictors".to_string());
+        }
+        let residuals = y - &design.dot(&beta);
+        let rss = residuals.mapv(|r| r * r).sum();
+        let centre = if self.fit_intercept { y.mean().unwrap_or(0.0) } else { 0.0 };
+        let tss = y.mapv(|v| (v - centre).powi(2)).sum();
+        let r_squared = if tss > 0.0 { 1.0 - rss / tss } else { f64::NAN };
+        let adj_r_squared = 1.0 - (1.0 - r_squared) * (n - first_slope) as f64 / df_resid;
+
+        self.fitted = Some(Fitted {
+            beta,
+            covariance,
+            transform,
+            sigma2: rss / df_resid,
+            df_resid,
+            r_squared,
+            adj_r_squared,
+            residuals,
+        });
+        Ok(())
+    }
+
+    /// Cyclic coordinate descent with soft thresholding on centred data
+    fn lasso_coordinate_descent(&self, x: &Array2<f64>, y: &Array1<f64>, alpha: f64) -> Result<Array1<f64>, String> {
+        let n = x.nrows() as f64;
+        let (x_mean, y_mean) = if self.fit_intercept {
+            (x.mean_axis(Axis(0)).ok_or("Empty design matrix")?, y.mean().unwrap_or(0.0))
+        } else {
+            (Array1::zeros(x.ncols()), 0.0)
+        };
+        let xc = x - &x_mean;
+        let mut residual = y.mapv(|v| v - y_mean);
+        let norms: Vec<f64> = xc.columns().into_iter().map(|c| c.dot(&c) / n).collect();
+        let mut slopes = Array1::<f64>::zeros(x.ncols());
+
+        for _ in 0..LASSO_MAX_ITER {
+            let mut largest_change: f64 = 0.0;
+            for j in 0..x.ncols() {
+                if norms[j] == 0.0 {
+                    continue;
+                }
+                let column = xc.column(j);
+                let rho = column.dot(&residual) / n + norms[j] * slopes[j];
+                let updated = rho.signum() * (rho.abs() - alpha).max(0.0) / norms[j];
+                let change = updated - slopes[j];
+                if change != 0.0 {
+                    residual.scaled_add(-change, &column);
+                    slopes[j] = updated;
+                    largest_change = largest_change.max(change.abs());
+                }
+            }
+            if largest_change < LASSO_TOLERANCE {
+                break;
+            }
+        }
+
+        if !self.fit_intercept {
+            return Ok(slopes);
+        }
+        let mut beta = Array1::<f64>::zeros(x.ncols() + 1);
+        beta[0] = y_mean - x_mean.dot(&slopes);
+        beta.slice_mut(s![1..]).assign(&slopes);
+        Ok(beta)
+    }
+
+    fn fitted(&self) -> Result<&Fitted, String> {
+        self.fitted.as_ref().ok_or_else(|| "Model must be fitted first".to_string())
+    }
+
+    /// Coefficients, standard errors, R² and residuals of the fit
+    pub fn summary(&self) -> Result<RegressionSummary, String> {
+        let fitted = self.fitted()?;
+        let distribution = StudentsT::new(0.0, 1.0, fitted.df_resid).map_err(|e| e.to_string())?;
+        let covariance = fitted
+            .covariance
+            .as_ref()
+            .map(|c| fitted.transform.dot(c).dot(&fitted.transform.t()));
+        let mut coefficients: Vec<Coefficient> = fitted
+            .beta
+            .iter()
+            .enumerate()
+            .map(|(j, &estimate)| {
+                let std_error = match &covariance {
+                    Some(covariance) => (fitted.sigma2 * covariance[[j, j]]).sqrt(),
+                    None => f64::NAN,
+                };
+                let t_value = estimate / std_error;
+                let p_value = if t_value.is_finite() {
+                    2.0 * distribution.cdf(-t_value.abs())
+                } else if t_value.is_nan() {
+                    f64::NAN
+                } else {
+                    0.0
+                };
+                Coefficient { estimate, std_error, t_value, p_value }
+            })
+            .collect();
+        let intercept = if self.fit_intercept { Some(coefficients.remove(0)) } else { None };
+
+        Ok(RegressionSummary {
+            intercept,
+            coefficients,
+            r_squared: fitted.r_squared,
+            adj_r_squared: fitted.adj_r_squared,
+            sigma: fitted.sigma2.sqrt(),
+            df_resid: fitted.df_resid,
+            residuals: fitted.residuals.clone(),
+        })
+    }
+
+    /// Predicted responses for new rows of predictors
+    pub fn predict(&self, x: &Array2<f64>) -> Result<Array1<f64>, String> {
+        let fitted = self.fitted()?;
+        let design = self.design(x);
+        if design.ncols() != fitted.beta.len() {
+            return Err("x has a different number of columns than the training data".to_string());
+        }
+        Ok(design.dot(&fitted.beta))
+    }
+
+    /// Prediction intervals for new observations, e.g. the range a raw
+    /// sensor reading maps to on a calibration curve.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - New rows of predictors
+    /// * `level` - Coverage probability, e.g. 0.95
+    ///
+    /// # Returns
+    ///
+    /// The predictions with lower and upper bounds; unavailable for lasso
+    pub fn prediction_intervals(&self, x: &Array2<f64>, level: f64) -> Result<PredictionIntervals, String> {
+        if !(level > 0.0 && level < 1.0) {
+            return Err("Level must be between 0 and 1".to_string());
+        }
+        let fitted = self.fitted()?;
+        let covariance = fitted
+            .covariance
+            .as_ref()
+            .ok_or_else(|| "Prediction intervals are not available for lasso".to_string())?;
+        let mean = self.predict(x)?;
+        let standardized = self.design(x).dot(&fitted.transform);
+        let distribution = StudentsT::new(0.0, 1.0, fitted.df_resid).map_err(|e| e.to_string())?;
+        let quantile = distribution.inverse_cdf(0.5 + level / 2.0);
+
+        let half_width: Array1<f64> = standardized
+            .rows()
+            .into_iter()
+            .map(|row| quantile * (fitted.sigma2 * (1.0 + row.dot(&covariance.dot(&row)))).sqrt())
+            .collect();
+        Ok(PredictionIntervals {
+            lower: &mean - &half_width,
+            upper: &mean + &half_width,
+            mean,
+        })
+    }
+}
+
+/// Expand predictors into all monomials up to `degree`, without the constant term.
+///
+/// Columns are ordered by degree, then lexicographically: for predictors
+/// `a, b` and degree 2 the result is `a, b, a², ab, b²`. A single predictor
+/// gives the powers `x, x², ..., x^degree` used for polynomial calibration curves.
+///
+/// # Arguments
+///
+/// * `x` - One row per observation and one column per predictor
+/// * `degree` - Highest total degree, at least 1
+///
+/// # Returns
+///
+/// The expanded design matrix
+pub fn polynomial_features(x: &Array2<f64>, degree: usize) -> Result<Array2<f64>, String> {
+    if degree == 0 {
+        return Err("Degree must be at least 1".to_string());
+    }
+    // Each monomial is the list of predictor indices it multiplies, in non-decreasing order
+    let mut monomials: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Vec<Vec<usize>> = vec![Vec::new()];
+    for _ in 0..degree {
+        let mut current = Vec::new();
+        for monomial in &previous {
+            let start = monomial.last().copied().unwrap_or(0);
+            for j in start..x.ncols() {
+                let mut next = monomial.clone();
+                next.push(j);
+                current.push(next);
+            }
+        }
+        monomials.extend(current.iter().cloned());
+        previous = current;
+    }
+
+    let mut features = Array2::<f64>::ones((x.nrows(), monomials.len()));
+    for (c, monomial) in monomials.iter().enumerate() {
+        for &j in monomial {
+            let column = x.column(j).to_owned();
+            let mut feature = features.column_mut(c);
+            feature *= &column;
+        }
+    }
+    Ok(features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // R's cars data: speed (mph) and stopping distance (ft)
+    const SPEED: [f64; 50] = [
+        4., 4., 7., 7., 8., 9., 10., 10., 10., 11., 11., 12., 12., 12., 12., 13., 13., 13., 13., 14., 14., 14., 14.,
+        15., 15., 15., 16., 16., 17., 17., 17., 18., 18., 18., 18., 19., 19., 19., 20., 20., 20., 20., 20., 22., 23.,
+        24., 24., 24., 24., 25.,
+    ];
+    const DIST: [f64; 50] = [
+        2., 10., 4., 22., 16., 10., 18., 26., 34., 17., 28., 14., 20., 24., 28., 26., 34., 34., 46., 26., 36., 60.,
+        80., 20., 26., 54., 32., 40., 32., 40., 50., 42., 56., 76., 84., 36., 46., 68., 32., 48., 52., 56., 64., 66.,
+        54., 70., 92., 93., 120., 85.,
+    ];
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    fn cars() -> (Array2<f64>, Array1<f64>) {
+        (Array2::from_shape_vec((50, 1), SPEED.to_vec()).unwrap(), Array1::from(DIST.to_vec()))
+    }
+
+    #[test]
+    fn test_ols_matches_r_cars() {
+        // Reference: summary(lm(dist ~ speed, data = cars))
+        let (x, y) = cars();
+        let mut model = LinearRegression::new();
+        model.fit(&x, &y).unwrap();
+        let summary = model.summary().unwrap();
+        let intercept = summary.intercept.unwrap();
+        assert!(close(intercept.estimate, -17.5791, 1e-4));
+        assert!(close(intercept.std_error, 6.7584, 1e-4));
+        assert!(close(summary.coefficients[0].estimate, 3.9324, 1e-4));
+        assert!(close(summary.coefficients[0].std_error, 0.4155, 1e-4));
+        assert!(close(summary.coefficients[0].t_value, 9.464, 1e-3));
+        assert!(summary.coefficients[0].p_value < 1.5e-12);
+        assert!(close(summary.r_squared, 0.6511, 1e-4));
+        assert!(close(summary.adj_r_squared, 0.6438, 1e-4));
+        assert!(close(summary.sigma, 15.38, 1e-2));
+        assert_eq!(summary.df_resid, 48.0);
+        assert!(close(summary.residuals.sum(), 0.0, 1e-9));
+
+        // predict(fit, data.frame(speed = 21), interval = "prediction")
+        let intervals = model.prediction_intervals(&Array2::from_elem((1, 1), 21.0), 0.95).unwrap();
+        assert!(close(intervals.mean[0], 65.0015, 1e-4));
+        assert!(close(intervals.lower[0], 33.4226, 1e-3));
+        assert!(close(intervals.upper[0], 96.5804, 1e-3));
+    }
+
+    #[test]
+    fn test_polynomial_calibration_curve() {
+        let x = Array2::from_shape_vec((3, 2), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let features = polynomial_features(&x, 2).unwrap();
+        assert_eq!(features.row(1).to_vec(), vec![3.0, 4.0, 9.0, 12.0, 16.0]);
+        assert_eq!(polynomial_features(&x, 3).unwrap().ncols(), 9);
+
+        // Quadratic thermistor-style curve is recovered exactly
+        let raw: Array1<f64> = (0..20).map(|i| i as f64 * 0.5).collect();
+        let temperature = raw.mapv(|r| 2.0 + 3.0 * r - 0.5 * r * r);
+        let design = polynomial_features(&raw.clone().insert_axis(Axis(1)), 2).unwrap();
+        let mut model = LinearRegression::new();
+        model.fit(&design, &temperature).unwrap();
+        let summary = model.summary().unwrap();
+        assert!(close(summary.intercept.unwrap().estimate, 2.0, 1e-9));
+        assert!(close(summary.coefficients[0].estimate, 3.0, 1e-9));
+        assert!(close(summary.coefficients[1].estimate, -0.5, 1e-9));
+        assert!(close(summary.r_squared, 1.0, 1e-12));
+    }
+
+    #[test]
+    fn test_polynomial_calibration_on_raw_adc_range() {
+        // Cubic over the upper half of 10-bit ADC counts, where the raw X'X
+        // spans about eighteen orders of magnitude. Reference computed in exact
+        // rational arithmetic from the normal equations.
+        let raw: Array1<f64> = (512..1024).step_by(4).map(|r| r as f64).collect();
+        let temperature: Array1<f64> = raw
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| 25.0 + 0.1 * r - 2e-4 * r * r + 1e-7 * r * r * r + ((i * 7) % 11) as f64 / 100.0 - 0.05)
+            .collect();
+        let mut model = LinearRegression::new();
+        model.fit(&polynomial_features(&raw.insert_axis(Axis(1)), 3).unwrap(), &temperature).unwrap();
+        let summary = model.summary().unwrap();
+        let mut estimates = vec![summary.intercept.unwrap()];
+        estimates.extend(summary.coefficients);
+        let reference = [
+            (24.85116649849869, 0.4793361380835373),
+            (0.1006164459344377, 1.94980676459964e-3),
+            (-2.008354695041847e-4, 2.590382240570149e-6),
+            (1.003707754207678e-7, 1.125440012803764e-9),
+        ];
+        for (coefficient, (estimate, std_error)) in estimates.iter().zip(reference) {
+            assert!(close(coefficient.estimate / estimate, 1.0, 1e-9));
+            assert!(close(coefficient.std_error / std_error, 1.0, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_ridge_shrinks_towards_zero() {
+        let (x, y) = cars();
+        let mut ols = LinearRegression::new();
+        ols.fit(&x, &y).unwrap();
+        let mut unpenalised = LinearRegression::ridge(0.0);
+        unpenalised.fit(&x, &y).unwrap();
+        assert_eq!(unpenalised.predict(&x).unwrap(), ols.predict(&x).unwrap());
+
+        let mut ridge = LinearRegression::ridge(5000.0);
+        ridge.fit(&x, &y).unwrap();
+        let slope = ridge.summary().unwrap().coefficients[0].estimate;
+        assert!(slope > 0.0 && slope < 3.9324);
+        // Stationarity of the penalised objective: X'(y - Xb) = alpha * b for the slope
+        let residuals = ridge.summary().unwrap().residuals;
+        assert!(close(x.column(0).dot(&residuals), 5000.0 * slope, 1e-6));
+        assert!(ridge.summary().unwrap().df_resid > 48.0);
+        assert!(ridge.prediction_intervals(&x, 0.9).is_ok());
+    }
+
+    #[test]
+    fn test_lasso_selects_predictors() {
+        // Orthogonal, centred predictors with x'x / n = 1 have a soft-threshold solution
+        let n = 40;
+        let mut x = Array2::<f64>::zeros((n, 2));
+        for i in 0..n {
+            x[[i, 0]] = if i % 2 == 0 { 1.0 } else { -1.0 };
+            x[[i, 1]] = if (i / 2) % 2 == 0 { 1.0 } else { -1.0 };
+        }
+        let y = x.column(0).mapv(|v| 10.0 + 2.0 * v) + x.column(1).mapv(|v| 0.3 * v);
+
+        let mut lasso = LinearRegression::lasso(0.5);
+        lasso.fit(&x, &y).unwrap();
+        let summary = lasso.summary().unwrap();
+        assert!(close(summary.coefficients[0].estimate, 1.5, 1e-9));
+        assert_eq!(summary.coefficients[1].estimate, 0.0);
+        assert!(close(summary.intercept.unwrap().estimate, 10.0, 1e-9));
+        assert!(summary.coefficients[0].std_error.is_nan());
+        assert_eq!(summary.df_resid, (n - 2) as f64);
+        assert!(lasso.prediction_intervals(&x, 0.95).is_err());
+
+        assert!(LinearRegression::new().summary().is_err());
+        assert!(LinearRegression::new().fit(&x, &y.slice(s![..10]).to_owned()).is_err());
+    }
+}
@@ -0,0 +1,484 @@
+// src/ml/rolling.rs
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// Which position of the window a rolling value is reported at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    /// Last sample of the window, only past values are used
+    Trailing,
+    /// Middle sample of the window, the one right of centre for even windows
+    Centered,
+    /// First sample of the window
+    Leading,
+}
+
+/// Values of a rolling statistic and the sample each one is reported at
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingSeries {
+    pub index: Vec<usize>,  // Position in the input series given by the alignment
+    pub values: Vec<f64>,
+}
+
+/// Sliding window over a series.
+///
+/// Only complete windows are evaluated. They start at 0, `step`,
+/// `2 * step`, ... and every statistic is reported at the sample chosen by
+/// `alignment`. Mean, variance, min and max cost O(n); median and quantiles
+/// cost O(n log w) and MAD O(n log² w) for a window of length w.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rolling {
+    pub window: usize,  // Samples per window
+    pub step: usize,  // Samples between the starts of consecutive windows
+    pub alignment: Alignment,
+}
+
+impl Rolling {
+    /// Trailing window of `window` samples evaluated at every sample
+    pub fn new(window: usize) -> Self {
+        Rolling {
+            window,
+            step: 1,
+            alignment: Alignment::Trailing,
+        }
+    }
+
+    pub fn mean(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.run(data, Moments::new(), |m| m.mean)
+    }
+
+    /// Sample variance (n - 1 denominator), NaN for windows of one sample
+    pub fn variance(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.run(data, Moments::new(), |m| {
+            if m.count > 1 { (m.m2 / (m.count - 1) as f64).max(0.0) } else { f64::NAN }
+        })
+    }
+
+    pub fn min(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.run(data, Extremum::new(Ordering::Less), Extremum::value)
+    }
+
+    pub fn max(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.run(data, Extremum::new(Ordering::Greater), Extremum::value)
+    }
+
+    pub fn median(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.quantile(data, 0.5)
+    }
+
+    /// Quantile with linear interpolation between order statistics
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The series
+    /// * `q` - Probability in [0, 1]
+    pub fn quantile(&self, data: &[f64], q: f64) -> Result<RollingSeries, String> {
+        if !(0.0..=1.0).contains(&q) {
+            return Err("Quantile must be between 0 and 1".to_string());
+        }
+        self.run(data, OrderStatistics::new(), |s| s.quantile(q))
+    }
+
+    /// Median absolute deviation from the window median, unscaled
+    pub fn mad(&self, data: &[f64]) -> Result<RollingSeries, String> {
+        self.run(data, OrderStatistics::new(), OrderStatistics::mad)
+    }
+
+    fn run<S: WindowState>(
+        &self,
+        data: &[f64],
+        mut state: S,
+        read: impl Fn(&S) -> f64,
+    ) -> Result<RollingSeries, String> {
+        if self.window == 0 || self.step == 0 {
+            return Err("Window and step must be at least 1".to_string());
+        }
+        if data.len() < self.window {
+            return Err("Not enough data for one window".to_string());
+        }
+        if data.iter().any(|v| v.is_nan()) {
+            return Err("Data contains NaN, impute the gaps first".to_string());
+        }
+        let offset = match self.alignment {
+            Alignment::Trailing => self.window - 1,
+            Alignment::Centered => self.window / 2,
+            Alignment::Leading => 0,
+        };
+
+        let mut index = Vec::new();
+        let mut values = Vec::new();
+        for (i, &value) in data.iter().enumerate() {
+            state.push(i, value);
+            if i >= self.window {
+                state.pop(i - self.window, data[i - self.window]);
+            }
+            if i + 1 >= self.window {
+                let start = i + 1 - self.window;
+                if start % self.step == 0 {
+                    index.push(start + offset);
+                    values.push(read(&state));
+                }
+            }
+        }
+        Ok(RollingSeries { index, values })
+    }
+}
+
+/// Incrementally maintained summary of the samples inside the window
+pub(crate) trait WindowState {
+    fn push(&mut self, index: usize, value: f64);
+    fn pop(&mut self, index: usize, value: f64);
+}
+
+/// Running mean and sum of squared deviations (Welford)
+struct Moments {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    fn new() -> Self {
+        Moments { count: 0, mean: 0.0, m2: 0.0 }
+    }
+}
+
+impl WindowState for Moments {
+    fn push(&mut self, _index: usize, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn pop(&mut self, _index: usize, value: f64) {
+        if self.count == 1 {
+            *self = Moments::new();
+            return;
+        }
+        let old_mean = self.mean;
+        self.count -= 1;
+        self.mean = (old_mean * (self.count + 1) as f64 - value) / self.count as f64;
+        self.m2 -= (value - old_mean) * (value - self.mean);
+    }
+}
+
+/// Monotonic deque whose front is the window minimum or maximum
+struct Extremum {
+    keep: Ordering,  // Less keeps the minimum, Greater the maximum
+    deque: VecDeque<(usize, f64)>,
+}
+
+impl Extremum {
+    fn new(keep: Ordering) -> Self {
+        Extremum { keep, deque: VecDeque::new() }
+    }
+
+    fn value(&self) -> f64 {
+        self.deque.front().map_or(f64::NAN, |&(_, v)| v)
+    }
+}
+
+impl WindowState for Extremum {
+    fn push(&mut self, index: usize, value: f64) {
+        while let Some(&(_, back)) = self.deque.back() {
+            if value.total_cmp(&back) == self.keep || value == back {
+                self.deque.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.deque.push_back((index, value));
+    }
+
+    fn pop(&mut self, index: usize, _value: f64) {
+        if self.deque.front().is_some_and(|&(i, _)| i == index) {
+            self.deque.pop_front();
+        }
+    }
+}
+
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: f64,
+    id: usize,  // Position in the series, breaks ties between equal values
+    priority: u64,
+    left: usize,
+    right: usize,
+    size: usize,
+}
+
+/// Order-statistic treap holding the samples of the window
+#[derive(Debug, Clone)]
+pub(crate) struct OrderStatistics {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: usize,
+    seed: u64,
+}
+
+impl OrderStatistics {
+    pub(crate) fn new() -> Self {
+        OrderStatistics {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NIL,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL { 0 } else { self.nodes[t].size }
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    fn update(&mut self, t: usize) {
+        self.nodes[t].size = 1 + self.size(self.nodes[t].left) + self.size(self.nodes[t].right);
+    }
+
+    /// Whether node `t` sorts before the key (value, id)
+    fn before(&self, t: usize, value: f64, id: usize) -> bool {
+        let node = &self.nodes[t];
+        node.value.total_cmp(&value).then(node.id.cmp(&id)) == Ordering::Less
+    }
+
+    /// Split into the nodes before (value, id) and the rest
+    fn split(&mut self, t: usize, value: f64, id: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        if self.before(t, value, id) {
+            let (left, right) = self.split(self.nodes[t].right, value, id);
+            self.nodes[t].right = left;
+            self.update(t);
+            (t, right)
+        } else {
+            let (left, right) = self.split(self.nodes[t].left, value, id);
+            self.nodes[t].left = right;
+            self.update(t);
+            (left, t)
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = right;
+            self.update(a);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = left;
+            self.update(b);
+            b
+        }
+    }
+
+    /// Value of rank `k`, 0 being the smallest
+    fn kth(&self, mut k: usize) -> f64 {
+        let mut t = self.root;
+        loop {
+            let left = self.size(self.nodes[t].left);
+            match k.cmp(&left) {
+                Ordering::Less => t = self.nodes[t].left,
+                Ordering::Equal => return self.nodes[t].value,
+                Ordering::Greater => {
+                    k -= left + 1;
+                    t = self.nodes[t].right;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn quantile(&self, q: f64) -> f64 {
+        let h = q * (self.len() - 1) as f64;
+        let lower = h.floor() as usize;
+        let fraction = h - lower as f64;
+        let low = self.kth(lower);
+        if fraction > 0.0 {
+            low + fraction * (self.kth(lower + 1) - low)
+        } else {
+            low
+        }
+    }
+
+    fn mad(&self) -> f64 {
+        let n = self.len();
+        let median = self.quantile(0.5);
+        // Deviations below and above the median form two ascending sequences
+        let split = (n - 1) / 2;
+        let below = |i: usize| median - self.kth(split - i);
+        let above = |j: usize| self.kth(split + 1 + j) - median;
+        let (len_below, len_above) = (split + 1, n - split - 1);
+        let deviation = |k: usize| kth_of_two(len_below, len_above, k, &below, &above);
+        if n % 2 == 1 {
+            deviation(n / 2)
+        } else {
+            (deviation(n / 2 - 1) + deviation(n / 2)) / 2.0
+        }
+    }
+}
+
+impl WindowState for OrderStatistics {
+    fn push(&mut self, index: usize, value: f64) {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let node = Node {
+            value,
+            id: index,
+            priority: self.seed,
+            left: NIL,
+            right: NIL,
+            size: 1,
+        };
+        let t = match self.free.pop() {
+            Some(t) => {
+                self.nodes[t] = node;
+                t
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        let (left, right) = self.split(self.root, value, index);
+        let left = self.merge(left, t);
+        self.root = self.merge(left, right);
+    }
+
+    fn pop(&mut self, index: usize, value: f64) {
+        let (left, rest) = self.split(self.root, value, index);
+        let (node, right) = self.split(rest, value, index + 1);
+        if node != NIL {
+            self.free.push(node);
+        }
+        self.root = self.merge(left, right);
+    }
+}
+
+/// Element of rank `k` in the union of two ascending sequences given by accessors
+fn kth_of_two(
+    len_a: usize,
+    len_b: usize,
+    k: usize,
+    a: &dyn Fn(usize) -> f64,
+    b: &dyn Fn(usize) -> f64,
+) -> f64 {
+    // Find how many of the k + 1 smallest elements come from `a`
+    let mut lo = (k + 1).saturating_sub(len_b);
+    let mut hi = (k + 1).min(len_a);
+    while lo < hi {
+        let i = (lo + hi) / 2;
+        if b(k - i) > a(i) {
+            lo = i + 1;
+        } else {
+            hi = i;
+        }
+    }
+    let j = k + 1 - lo;
+    let from_a = if lo > 0 { a(lo - 1) } else { f64::NEG_INFINITY };
+    let from_b = if j > 0 { b(j - 1) } else { f64::NEG_INFINITY };
+    from_a.max(from_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::uniform_noise;
+
+    fn noisy_series(n: usize) -> Vec<f64> {
+        // Rounded so the windows contain ties
+        uniform_noise(n, 42)
+            .iter()
+            .enumerate()
+            .map(|(i, noise)| ((i as f64 * 0.2).sin() * 5.0 + noise * 4.0).round())
+            .collect()
+    }
+
+    fn sorted(window: &[f64]) -> Vec<f64> {
+        let mut values = window.to_vec();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        values
+    }
+
+    fn brute_quantile(window: &[f64], q: f64) -> f64 {
+        let values = sorted(window);
+        let h = q * (values.len() - 1) as f64;
+        let lower = h.floor() as usize;
+        let upper = h.ceil() as usize;
+        values[lower] + (h - lower as f64) * (values[upper] - values[lower])
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let data = noisy_series(200);
+        for &window in &[1, 2, 5, 8, 31] {
+            let rolling = Rolling { window, step: 3, alignment: Alignment::Leading };
+            let mean = rolling.mean(&data).unwrap();
+            let variance = rolling.variance(&data).unwrap();
+            let min = rolling.min(&data).unwrap();
+            let max = rolling.max(&data).unwrap();
+            let median = rolling.median(&data).unwrap();
+            let q90 = rolling.quantile(&data, 0.9).unwrap();
+            let mad = rolling.mad(&data).unwrap();
+
+            assert_eq!(mean.index, (0..=data.len() - window).step_by(3).collect::<Vec<_>>());
+            for (k, &start) in mean.index.iter().enumerate() {
+                let w = &data[start..start + window];
+                let m = w.iter().sum::<f64>() / window as f64;
+                assert!((mean.values[k] - m).abs() < 1e-9);
+                if window > 1 {
+                    let v = w.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (window - 1) as f64;
+                    assert!((variance.values[k] - v).abs() < 1e-9);
+                } else {
+                    assert!(variance.values[k].is_nan());
+                }
+                assert_eq!(min.values[k], sorted(w)[0]);
+                assert_eq!(max.values[k], sorted(w)[window - 1]);
+                let med = brute_quantile(w, 0.5);
+                assert_eq!(median.values[k], med);
+                assert!((q90.values[k] - brute_quantile(w, 0.9)).abs() < 1e-12);
+                let deviations: Vec<f64> = w.iter().map(|x| (x - med).abs()).collect();
+                assert_eq!(mad.values[k], brute_quantile(&deviations, 0.5));
+            }
+        }
+    }
+
+    #[test]
+    fn test_alignment() {
+        let data: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let mut rolling = Rolling::new(4);
+        let trailing = rolling.mean(&data).unwrap();
+        assert_eq!(trailing.index[0], 3);
+        assert_eq!(trailing.values[0], 1.5);
+        assert_eq!(trailing.values.len(), 7);
+
+        rolling.alignment = Alignment::Centered;
+        assert_eq!(rolling.max(&data).unwrap().index[0], 2);
+
+        rolling.step = 4;
+        let stepped = rolling.min(&data).unwrap();
+        assert_eq!(stepped.index, vec![2, 6]);
+        assert_eq!(stepped.values, vec![0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(Rolling::new(0).mean(&[1.0, 2.0]).is_err());
+        assert!(Rolling::new(3).median(&[1.0, 2.0]).is_err());
+        assert!(Rolling::new(2).max(&[1.0, f64::NAN, 2.0]).is_err());
+        assert!(Rolling::new(2).quantile(&[1.0, 2.0], 1.5).is_err());
+    }
+}
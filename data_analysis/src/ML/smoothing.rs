@@ -0,0 +1,522 @@
+// src/ml/smoothing.rs
+use ndarray::{s, Array1};
+
+use crate::ML::data_imputation::simple_exponential_smoothing;
+use crate::ML::forecaster::Forecaster;
+use crate::ML::optimize::{bounded, nelder_mead, unbounded};
+
+/// Bounds of the fitted smoothing weights and of the damping factor
+const WEIGHT_BOUNDS: (f64, f64) = (1e-4, 0.9999);
+const DAMPING_BOUNDS: (f64, f64) = (0.8, 0.98);
+
+/// Simple exponential smoothing as a forecasting model.
+///
+/// Wraps `simple_exponential_smoothing` and forecasts the last smoothed level
+/// for every step ahead.
+pub struct SimpleExponentialSmoothing {
+    pub alpha: f64,
+    history: Option<Array1<f64>>,  // Series the model was fitted on
+    smoothed: Option<Array1<f64>>,  // Smoothed level after each observation
+}
+
+impl SimpleExponentialSmoothing {
+    /// Create a new model with a fixed smoothing factor (0 < alpha <= 1)
+    pub fn new(alpha: f64) -> Self {
+        SimpleExponentialSmoothing {
+            alpha,
+            history: None,
+            smoothed: None,
+        }
+    }
+}
+
+impl Forecaster for SimpleExponentialSmoothing {
+    fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+        if !(self.alpha > 0.0 && self.alpha <= 1.0) {
+            return Err("Alpha must be in (0, 1]".to_string());
+        }
+        if data.len() < 2 {
+            return Err("At least two observations are needed to fit the model".to_string());
+        }
+
+        let smoothed = simple_exponential_smoothing(&data.to_vec(), self.alpha);
+        self.smoothed = Some(Array1::from(smoothed));
+        self.history = Some(data.clone());
+        Ok(())
+    }
+
+    fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        match &self.smoothed {
+            Some(smoothed) => Ok(Array1::from_elem(horizon, smoothed[smoothed.len() - 1])),
+            None => Err("Model must be fitted before forecasting".to_string()),
+        }
+    }
+
+    /// The level after observation `t - 1` is the prediction for `t`, so the
+    /// fitted values start at the second observation.
+    fn fitted_values(&self) -> Result<Array1<f64>, String> {
+        match &self.smoothed {
+            Some(smoothed) => Ok(smoothed.slice(s![..-1]).to_owned()),
+            None => Err("Model must be fitted before computing fitted values".to_string()),
+        }
+    }
+
+    fn residuals(&self) -> Result<Array1<f64>, String> {
+        match &self.history {
+            Some(history) => Ok(&history.slice(s![1..]) - &self.fitted_values()?),
+            None => Err("Model must be fitted before computing residuals".to_string()),
+        }
+    }
+
+    /// Every step ahead adds `alpha^2 * sigma2` to the one-step variance
+    fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        let residuals = self.residuals()?;
+        let sigma2 = residuals.dot(&residuals) / residuals.len() as f64;
+        let alpha2 = self.alpha * self.alpha;
+        Ok(Array1::from_shape_fn(horizon, |h| sigma2 * (1.0 + h as f64 * alpha2)))
+    }
+}
+
+/// How the seasonal component combines with level and trend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seasonality {
+    Additive,
+    Multiplicative,
+}
+
+/// Fitted smoothing parameters.
+///
+/// `gamma` is only used by seasonal models and `phi` is 1.0 for undamped trends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingParams {
+    pub alpha: f64,  // Level
+    pub beta: f64,  // Trend
+    pub gamma: f64,  // Seasonal
+    pub phi: f64,  // Trend damping
+}
+
+/// Components after the last observation, what the forecasts are built from
+#[derive(Debug, Clone)]
+struct SmoothingState {
+    level: f64,
+    trend: f64,
+    seasonals: Vec<f64>,  // Last `period` seasonal indices, oldest first
+}
+
+/// Structure shared by Holt and Holt-Winters
+#[derive(Debug, Clone, Copy)]
+struct SmoothingSpec {
+    damped: bool,
+    season: Option<(usize, Seasonality)>,
+}
+
+impl SmoothingSpec {
+    /// Observations consumed by the initialisation before the first one-step prediction
+    fn warmup(&self) -> usize {
+        match self.season {
+            Some((period, _)) => period,
+            None => 1,
+        }
+    }
+
+    /// Initial level, trend and seasonal indices from the start of the series
+    fn initial_state(&self, data: &Array1<f64>) -> SmoothingState {
+        match self.season {
+            None => SmoothingState {
+                level: data[0],
+                trend: data[1] - data[0],
+                seasonals: Vec::new(),
+            },
+            Some((period, seasonality)) => {
+                let m = period as f64;
+                let first = data.slice(s![..period]).sum() / m;
+                let second = data.slice(s![period..2 * period]).sum() / m;
+                let seasonals = (0..period)
+                    .map(|i| match seasonality {
+                        Seasonality::Additive => data[i] - first,
+                        Seasonality::Multiplicative => data[i] / first,
+                    })
+                    .collect();
+                SmoothingState {
+                    level: first,
+                    trend: (second - first) / m,
+                    seasonals,
+                }
+            }
+        }
+    }
+
+    /// Run the smoothing recursions over the data.
+    ///
+    /// Returns the one-step-ahead predictions (starting after the warm-up)
+    /// and the state after the last observation.
+    fn filter(&self, params: &SmoothingParams, data: &Array1<f64>) -> (Vec<f64>, SmoothingState) {
+        let mut state = self.initial_state(data);
+        let warmup = self.warmup();
+        let mut fitted = Vec::with_capacity(data.len() - warmup);
+
+        for t in warmup..data.len() {
+            let y = data[t];
+            let previous_level = state.level;
+            let damped_trend = params.phi * state.trend;
+            let base = previous_level + damped_trend;
+
+            match self.season {
+                None => {
+                    fitted.push(base);
+                    state.level = params.alpha * y + (1.0 - params.alpha) * base;
+                }
+                Some((_, seasonality)) => {
+                    let seasonal = state.seasonals.remove(0);
+                    let (prediction, deseasonalised, new_seasonal) = match seasonality {
+                        Seasonality::Additive => (
+                            base + seasonal,
+                            y - seasonal,
+                            params.gamma * (y - base) + (1.0 - params.gamma) * seasonal,
+                        ),
+                        Seasonality::Multiplicative => (
+                            base * seasonal,
+                            y / seasonal,
+                            params.gamma * (y / base) + (1.0 - params.gamma) * seasonal,
+                        ),
+                    };
+                    fitted.push(prediction);
+                    state.level = params.alpha * deseasonalised + (1.0 - params.alpha) * base;
+                    state.seasonals.push(new_seasonal);
+                }
+            }
+
+            state.trend = params.beta * (state.level - previous_level) + (1.0 - params.beta) * damped_trend;
+        }
+
+        (fitted, state)
+    }
+
+    /// h-step forecasts from the state after the last observation
+    fn forecast(&self, params: &SmoothingParams, state: &SmoothingState, horizon: usize) -> Array1<f64> {
+        let mut damping_sum = 0.0;
+        let mut phi_power = 1.0;
+
+        Array1::from_shape_fn(horizon, |h| {
+            phi_power *= params.phi;
+            damping_sum += phi_power;
+            let base = state.level + damping_sum * state.trend;
+            match self.season {
+                None => base,
+                Some((period, Seasonality::Additive)) => base + state.seasonals[h % period],
+                Some((period, Seasonality::Multiplicative)) => base * state.seasonals[h % period],
+            }
+        })
+    }
+
+    /// Forecast error variances from the error-correction form of the recursions.
+    ///
+    /// The j-step-ahead error enters the forecast with weight
+    /// `alpha * (1 + beta * (phi + ... + phi^j)) + gamma * [j is a multiple of the period]`.
+    /// Exact for additive models, an approximation for multiplicative seasonality.
+    fn variance(&self, params: &SmoothingParams, sigma2: f64, horizon: usize) -> Array1<f64> {
+        let mut damping_sum = 0.0;
+        let mut phi_power = 1.0;
+        let mut cumulative = 1.0;
+        let mut variance = Array1::zeros(horizon);
+
+        for h in 0..horizon {
+            variance[h] = sigma2 * cumulative;
+
+            // Weight of the error made h + 1 steps before the next forecast
+            let j = h + 1;
+            phi_power *= params.phi;
+            damping_sum += phi_power;
+            let mut weight = params.alpha * (1.0 + params.beta * damping_sum);
+            if let Some((period, _)) = self.season {
+                if j % period == 0 {
+                    weight += params.gamma;
+                }
+            }
+            cumulative += weight * weight;
+        }
+
+        variance
+    }
+
+    /// Fit the smoothing parameters by minimising the one-step-ahead SSE
+    fn fit_params(&self, data: &Array1<f64>) -> SmoothingParams {
+        let seasonal = self.season.is_some();
+        let (w_low, w_high) = WEIGHT_BOUNDS;
+        let (d_low, d_high) = DAMPING_BOUNDS;
+
+        // Unconstrained vector layout: [alpha, beta, gamma?, phi?]
+        let to_params = |x: &[f64]| {
+            let mut params = SmoothingParams {
+                alpha: bounded(x[0], w_low, w_high),
+                beta: bounded(x[1], w_low, w_high),
+                gamma: 0.0,
+                phi: 1.0,
+            };
+            let mut next = 2;
+            if seasonal {
+                params.gamma = bounded(x[next], w_low, w_high);
+                next += 1;
+            }
+            if self.damped {
+                params.phi = bounded(x[next], d_low, d_high);
+            }
+            params
+        };
+
+        let sse = |x: &[f64]| {
+            let (fitted, _) = self.filter(&to_params(x), data);
+            fitted
+                .iter()
+                .zip(data.iter().skip(self.warmup()))
+                .map(|(f, y)| (y - f).powi(2))
+                .sum::<f64>()
+        };
+
+        let mut initial = vec![unbounded(0.3, w_low, w_high), unbounded(0.1, w_low, w_high)];
+        if seasonal {
+            initial.push(unbounded(0.1, w_low, w_high));
+        }
+        if self.damped {
+            initial.push(unbounded(0.9, d_low, d_high));
+        }
+
+        let (best, _) = nelder_mead(sse, &initial, 1.0, 2000, 1e-10);
+        to_params(&best)
+    }
+}
+
+/// Fitted model shared by `Holt` and `HoltWinters`
+#[derive(Debug, Clone)]
+struct SmoothingFit {
+    params: SmoothingParams,
+    state: SmoothingState,
+    fitted: Array1<f64>,
+    residuals: Array1<f64>,
+    sigma2: f64,  // Mean squared one-step error
+}
+
+impl SmoothingFit {
+    fn new(spec: &SmoothingSpec, params: SmoothingParams, data: &Array1<f64>) -> Self {
+        let (fitted, state) = spec.filter(&params, data);
+        let fitted = Array1::from(fitted);
+        let residuals = &data.slice(s![spec.warmup()..]) - &fitted;
+        let sigma2 = residuals.dot(&residuals) / residuals.len() as f64;
+        SmoothingFit { params, state, fitted, residuals, sigma2 }
+    }
+}
+
+/// Implements `Forecaster` for a model with `spec()`, `check(data)` and a
+/// `fit: Option<SmoothingFit>` field
+macro_rules! smoothing_forecaster {
+    ($model:ty) => {
+        impl Forecaster for $model {
+            fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+                self.check(data)?;
+                let spec = self.spec();
+                let params = spec.fit_params(data);
+                self.fit = Some(SmoothingFit::new(&spec, params, data));
+                Ok(())
+            }
+
+            fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(self.spec().forecast(&fit.params, &fit.state, horizon)),
+                    None => Err("Model must be fitted before forecasting".to_string()),
+                }
+            }
+
+            fn fitted_values(&self) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.fitted.clone()),
+                    None => Err("Model must be fitted before computing fitted values".to_string()),
+                }
+            }
+
+            fn residuals(&self) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(fit.residuals.clone()),
+                    None => Err("Model must be fitted before computing residuals".to_string()),
+                }
+            }
+
+            fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+                match &self.fit {
+                    Some(fit) => Ok(self.spec().variance(&fit.params, fit.sigma2, horizon)),
+                    None => Err("Model must be fitted before computing forecast variance".to_string()),
+                }
+            }
+        }
+    };
+}
+
+/// Holt's linear trend method, optionally with a damped trend.
+///
+/// Smoothing weights (and the damping factor) are fitted by minimising the
+/// one-step-ahead sum of squared errors.
+pub struct Holt {
+    pub damped: bool,
+    fit: Option<SmoothingFit>,
+}
+
+impl Holt {
+    /// Create a new Holt model
+    pub fn new(damped: bool) -> Self {
+        Holt { damped, fit: None }
+    }
+
+    fn spec(&self) -> SmoothingSpec {
+        SmoothingSpec { damped: self.damped, season: None }
+    }
+
+    fn check(&self, data: &Array1<f64>) -> Result<(), String> {
+        if data.len() < 3 {
+            return Err("At least three observations are needed to fit Holt's method".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fitted smoothing parameters, `None` before `fit`
+    pub fn params(&self) -> Option<SmoothingParams> {
+        self.fit.as_ref().map(|fit| fit.params)
+    }
+}
+
+smoothing_forecaster!(Holt);
+
+/// Holt-Winters seasonal method with additive or multiplicative seasonality.
+///
+/// Level, trend and seasonal weights (and the damping factor when `damped`)
+/// are fitted by minimising the one-step-ahead sum of squared errors. The
+/// first two seasons initialise the components.
+pub struct HoltWinters {
+    pub period: usize,
+    pub seasonality: Seasonality,
+    pub damped: bool,
+    fit: Option<SmoothingFit>,
+}
+
+impl HoltWinters {
+    /// Create a new Holt-Winters model for a seasonal period (in samples)
+    pub fn new(period: usize, seasonality: Seasonality, damped: bool) -> Self {
+        HoltWinters { period, seasonality, damped, fit: None }
+    }
+
+    fn spec(&self) -> SmoothingSpec {
+        SmoothingSpec {
+            damped: self.damped,
+            season: Some((self.period, self.seasonality)),
+        }
+    }
+
+    fn check(&self, data: &Array1<f64>) -> Result<(), String> {
+        if self.period < 2 {
+            return Err("Seasonal period must be at least 2".to_string());
+        }
+        if data.len() < 2 * self.period + 1 {
+            return Err("At least two full seasons are needed to fit Holt-Winters".to_string());
+        }
+        if self.seasonality == Seasonality::Multiplicative && data.iter().any(|&y| y <= 0.0) {
+            return Err("Multiplicative seasonality requires strictly positive data".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fitted smoothing parameters, `None` before `fit`
+    pub fn params(&self) -> Option<SmoothingParams> {
+        self.fit.as_ref().map(|fit| fit.params)
+    }
+}
+
+smoothing_forecaster!(HoltWinters);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holt_recovers_linear_trend() {
+        let data = Array1::from((0..20).map(|t| 4.0 + 1.5 * t as f64).collect::<Vec<f64>>());
+        let mut model = Holt::new(false);
+        model.fit(&data).unwrap();
+
+        let forecasts = model.predict(3).unwrap();
+        for (h, value) in forecasts.iter().enumerate() {
+            assert!((value - (4.0 + 1.5 * (20 + h) as f64)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_damped_holt_flattens_forecasts() {
+        let data = Array1::from(vec![10.0, 12.0, 13.5, 15.2, 16.1, 17.9, 18.6, 20.3, 21.0, 22.4]);
+        let mut model = Holt::new(true);
+        model.fit(&data).unwrap();
+
+        let phi = model.params().unwrap().phi;
+        assert!((DAMPING_BOUNDS.0..=DAMPING_BOUNDS.1).contains(&phi));
+
+        // Damped increments shrink by phi every step
+        let forecasts = model.predict(3).unwrap();
+        let first_step = forecasts[1] - forecasts[0];
+        let second_step = forecasts[2] - forecasts[1];
+        assert!((second_step - phi * first_step).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_holt_winters_additive_daily_cycle() {
+        let period = 6;
+        let season = [2.0, 4.0, 1.0, -1.0, -4.0, -2.0];
+        let data = Array1::from(
+            (0..48).map(|t| 20.0 + 0.1 * t as f64 + season[t % period]).collect::<Vec<f64>>(),
+        );
+        let mut model = HoltWinters::new(period, Seasonality::Additive, false);
+        model.fit(&data).unwrap();
+
+        let forecasts = model.predict(period).unwrap();
+        for (h, value) in forecasts.iter().enumerate() {
+            let t = 48 + h;
+            let expected = 20.0 + 0.1 * t as f64 + season[t % period];
+            assert!((value - expected).abs() < 0.05, "{} vs {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn test_holt_winters_multiplicative() {
+        let period = 4;
+        let season = [1.2, 0.9, 0.8, 1.1];
+        let data = Array1::from(
+            (0..40).map(|t| (50.0 + t as f64) * season[t % period]).collect::<Vec<f64>>(),
+        );
+        let mut model = HoltWinters::new(period, Seasonality::Multiplicative, false);
+        model.fit(&data).unwrap();
+
+        let forecasts = model.predict(period).unwrap();
+        for (h, value) in forecasts.iter().enumerate() {
+            let t = 40 + h;
+            let expected = (50.0 + t as f64) * season[t % period];
+            assert!((value - expected).abs() / expected < 0.02, "{} vs {}", value, expected);
+        }
+
+        let residuals = model.residuals().unwrap();
+        assert_eq!(residuals.len(), 40 - period);
+    }
+
+    #[test]
+    fn test_holt_winters_needs_two_seasons() {
+        let data = Array1::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let mut model = HoltWinters::new(4, Seasonality::Additive, false);
+        assert!(model.fit(&data).is_err());
+    }
+
+    #[test]
+    fn test_holt_variance_matches_error_correction_weights() {
+        let spec = SmoothingSpec { damped: false, season: None };
+        let params = SmoothingParams { alpha: 0.5, beta: 0.2, gamma: 0.0, phi: 1.0 };
+        let variance = spec.variance(&params, 2.0, 3);
+        // Weights alpha * (1 + beta * j): 0.6 and 0.7
+        let expected = [2.0, 2.0 * (1.0 + 0.36), 2.0 * (1.0 + 0.36 + 0.49)];
+        for (a, b) in variance.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+// src/ml/spectral.rs
+use std::f64::consts::PI;
+
+use ndarray::{s, Array1};
+
+/// Taper applied to each segment before the Fourier transform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+}
+
+impl Window {
+    /// Periodic window weights of length `n`, the form used for spectral estimation
+    pub fn weights(&self, n: usize) -> Array1<f64> {
+        Array1::from_shape_fn(n, |i| {
+            let phase = 2.0 * PI * i as f64 / n as f64;
+            match self {
+                Window::Rectangular => 1.0,
+                Window::Hann => 0.5 - 0.5 * phase.cos(),
+                Window::Hamming => 0.54 - 0.46 * phase.cos(),
+            }
+        })
+    }
+}
+
+/// A peak of the spectrum
+#[derive(Debug, Clone, PartialEq)]
+pub struct Period {
+    pub frequency: f64,  // Cycles per sample
+    pub samples: f64,  // Period length in samples
+    pub seconds: Option<f64>,  // Period length in seconds, when the sampling interval is known
+    pub power: f64,
+}
+
+/// One-sided power spectral density
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    pub frequencies: Array1<f64>,  // Cycles per sample, from 0 to 0.5
+    pub power: Array1<f64>,  // Power per unit frequency
+}
+
+impl Spectrum {
+    /// Strongest local maxima of the spectrum, excluding the zero frequency.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of periods to report
+    /// * `sampling_interval` - Seconds between samples, to also express the periods in seconds
+    ///
+    /// # Returns
+    ///
+    /// The peaks sorted by decreasing power
+    pub fn dominant_periods(&self, count: usize, sampling_interval: Option<f64>) -> Vec<Period> {
+        let n = self.power.len();
+        let mut peaks: Vec<Period> = (1..n)
+            .filter(|&k| {
+                let left = self.power[k - 1];
+                let right = if k + 1 < n { self.power[k + 1] } else { f64::NEG_INFINITY };
+                self.power[k] > left && self.power[k] >= right
+            })
+            .map(|k| {
+                let samples = 1.0 / self.frequencies[k];
+                Period {
+                    frequency: self.frequencies[k],
+                    samples,
+                    seconds: sampling_interval.map(|dt| samples * dt),
+                    power: self.power[k],
+                }
+            })
+            .collect();
+
+        peaks.sort_by(|a, b| b.power.partial_cmp(&a.power).unwrap_or(std::cmp::Ordering::Equal));
+        peaks.truncate(count);
+        peaks
+    }
+}
+
+/// Periodogram of a series after removing its mean.
+///
+/// Scaled as a one-sided density with unit sampling frequency, so summing
+/// the power times the frequency spacing gives the (windowed) variance.
+///
+/// # Arguments
+///
+/// * `data` - The series, without missing values
+/// * `window` - Taper applied to the whole series
+pub fn periodogram(data: &Array1<f64>, window: Window) -> Result<Spectrum, String> {
+    if data.len() < 2 {
+        return Err("At least two observations are needed for a periodogram".to_string());
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err("Spectral analysis requires finite values, impute gaps first".to_string());
+    }
+    Ok(segment_spectrum(&data.to_vec(), &window.weights(data.len())))
+}
+
+/// Welch's power spectral density estimate: the average periodogram of
+/// overlapping, windowed segments, trading resolution for lower variance.
+///
+/// # Arguments
+///
+/// * `data` - The series, without missing values
+/// * `segment_length` - Samples per segment, which sets the frequency resolution
+/// * `overlap` - Samples shared by consecutive segments, usually half the segment
+/// * `window` - Taper applied to every segment, usually `Window::Hann`
+pub fn welch(data: &Array1<f64>, segment_length: usize, overlap: usize, window: Window) -> Result<Spectrum, String> {
+    if segment_length < 2 || segment_length > data.len() {
+        return Err("Segment length must be between 2 and the length of the series".to_string());
+    }
+    if overlap >= segment_length {
+        return Err("Overlap must be shorter than the segment".to_string());
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err("Spectral analysis requires finite values, impute gaps first".to_string());
+    }
+
+    let weights = window.weights(segment_length);
+    let step = segment_length - overlap;
+    let mut segments = 0;
+    let mut total: Option<Spectrum> = None;
+    let mut start = 0;
+    while start + segment_length <= data.len() {
+        let segment = data.slice(s![start..start + segment_length]).to_vec();
+        let spectrum = segment_spectrum(&segment, &weights);
+        total = Some(match total {
+            Some(sum) => Spectrum { power: sum.power + spectrum.power, ..sum },
+            None => spectrum,
+        });
+        segments += 1;
+        start += step;
+    }
+
+    let mut spectrum = total.ok_or_else(|| "No complete segment in the series".to_string())?;
+    spectrum.power /= segments as f64;
+    Ok(spectrum)
+}
+
+/// Density-scaled one-sided periodogram of one mean-removed, windowed segment
+fn segment_spectrum(segment: &[f64], weights: &Array1<f64>) -> Spectrum {
+    let n = segment.len();
+    let mean = segment.iter().sum::<f64>() / n as f64;
+    let tapered: Vec<f64> = segment.iter().zip(weights.iter()).map(|(y, w)| (y - mean) * w).collect();
+    let (re, im) = dft(&tapered);
+
+    let scale = weights.iter().map(|w| w * w).sum::<f64>();
+    let bins = n / 2 + 1;
+    let power = Array1::from_shape_fn(bins, |k| {
+        let density = (re[k] * re[k] + im[k] * im[k]) / scale;
+        // Negative frequencies fold onto positive ones, except at 0 and Nyquist
+        if k == 0 || (n % 2 == 0 && k == n / 2) { density } else { 2.0 * density }
+    });
+    let frequencies = Array1::from_shape_fn(bins, |k| k as f64 / n as f64);
+
+    Spectrum { frequencies, power }
+}
+
+/// Discrete Fourier transform of a real sequence of any length.
+///
+/// Powers of two use radix-2 FFT directly, other lengths go through
+/// Bluestein's chirp-z algorithm so the cost stays O(n log n).
+fn dft(x: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let n = x.len();
+    if n.is_power_of_two() {
+        let mut re = x.to_vec();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im, false);
+        return (re, im);
+    }
+
+    // Chirp w_k = exp(-i pi k^2 / n), with k^2 reduced mod 2n to keep the angle accurate
+    let chirp: Vec<(f64, f64)> = (0..n)
+        .map(|k| {
+            let angle = PI * ((k * k) % (2 * n)) as f64 / n as f64;
+            (angle.cos(), -angle.sin())
+        })
+        .collect();
+
+    let m = (2 * n - 1).next_power_of_two();
+    let (mut a_re, mut a_im) = (vec![0.0; m], vec![0.0; m]);
+    let (mut b_re, mut b_im) = (vec![0.0; m], vec![0.0; m]);
+    for k in 0..n {
+        a_re[k] = x[k] * chirp[k].0;
+        a_im[k] = x[k] * chirp[k].1;
+        b_re[k] = chirp[k].0;
+        b_im[k] = -chirp[k].1;
+        if k > 0 {
+            b_re[m - k] = chirp[k].0;
+            b_im[m - k] = -chirp[k].1;
+        }
+    }
+
+    // Circular convolution through the power-of-two FFT
+    fft(&mut a_re, &mut a_im, false);
+    fft(&mut b_re, &mut b_im, false);
+    for k in 0..m {
+        let re = a_re[k] * b_re[k] - a_im[k] * b_im[k];
+        let im = a_re[k] * b_im[k] + a_im[k] * b_re[k];
+        a_re[k] = re;
+        a_im[k] = im;
+    }
+    fft(&mut a_re, &mut a_im, true);
+
+    let mut re = vec![0.0; n];
+    let mut im = vec![0.0; n];
+    for k in 0..n {
+        re[k] = a_re[k] * chirp[k].0 - a_im[k] * chirp[k].1;
+        im[k] = a_re[k] * chirp[k].1 + a_im[k] * chirp[k].0;
+    }
+    (re, im)
+}
+
+/// In-place iterative radix-2 FFT, the inverse includes the 1/n factor
+fn fft(re: &mut [f64], im: &mut [f64], inverse: bool) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let angle = sign * 2.0 * PI / length as f64;
+        for start in (0..n).step_by(length) {
+            for k in 0..length / 2 {
+                let (w_re, w_im) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                let (i, j) = (start + k, start + k + length / 2);
+                let t_re = re[j] * w_re - im[j] * w_im;
+                let t_im = re[j] * w_im + im[j] * w_re;
+                re[j] = re[i] - t_re;
+                im[j] = im[i] - t_im;
+                re[i] += t_re;
+                im[i] += t_im;
+            }
+        }
+        length <<= 1;
+    }
+
+    if inverse {
+        for k in 0..n {
+            re[k] /= n as f64;
+            im[k] /= n as f64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::uniform_noise;
+
+    #[test]
+    fn test_dft_matches_naive() {
+        for &n in &[8, 15, 100] {
+            let x = uniform_noise(n, n as u64);
+            let (re, im) = dft(&x);
+            for k in 0..n {
+                let (mut naive_re, mut naive_im) = (0.0, 0.0);
+                for (t, value) in x.iter().enumerate() {
+                    let angle = -2.0 * PI * (k * t) as f64 / n as f64;
+                    naive_re += value * angle.cos();
+                    naive_im += value * angle.sin();
+                }
+                assert!((re[k] - naive_re).abs() < 1e-9 && (im[k] - naive_im).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_weights() {
+        let hann = Window::Hann.weights(4);
+        let hamming = Window::Hamming.weights(4);
+        for (w, expected) in hann.iter().zip(&[0.0, 0.5, 1.0, 0.5]) {
+            assert!((w - expected).abs() < 1e-12);
+        }
+        for (w, expected) in hamming.iter().zip(&[0.08, 0.54, 1.0, 0.54]) {
+            assert!((w - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_periodogram_peak_and_parseval() {
+        // Period of 10 samples over a length that is not a power of two
+        let e = uniform_noise(100, 1);
+        let data = Array1::from_shape_fn(100, |t| 3.0 + 2.0 * (2.0 * PI * t as f64 / 10.0).sin() + e[t]);
+        let spectrum = periodogram(&data, Window::Rectangular).unwrap();
+
+        let peak = &spectrum.dominant_periods(1, None)[0];
+        assert!((peak.samples - 10.0).abs() < 1e-9);
+
+        // Total power equals the variance around the mean
+        let mean = data.mean().unwrap();
+        let variance = data.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / 100.0;
+        assert!((spectrum.power.sum() / 100.0 - variance).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_welch_finds_two_periods_in_seconds() {
+        let e = uniform_noise(1200, 9);
+        let data = Array1::from_shape_fn(1200, |t| {
+            let t = t as f64;
+            2.0 * (2.0 * PI * t / 12.0).sin() + (2.0 * PI * t / 5.0).cos() + e[t as usize]
+        });
+        let spectrum = welch(&data, 120, 60, Window::Hann).unwrap();
+        let periods = spectrum.dominant_periods(2, Some(60.0));
+
+        assert!((periods[0].samples - 12.0).abs() < 0.5);
+        assert!((periods[0].seconds.unwrap() - 720.0).abs() < 30.0);
+        assert!((periods[1].samples - 5.0).abs() < 0.3);
+        assert!(periods[0].power > periods[1].power);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let data = Array1::from(vec![1.0, f64::NAN, 3.0]);
+        assert!(periodogram(&data, Window::Hann).is_err());
+        assert!(welch(&Array1::zeros(10), 20, 5, Window::Hann).is_err());
+        assert!(welch(&Array1::zeros(10), 4, 4, Window::Hann).is_err());
+    }
+}
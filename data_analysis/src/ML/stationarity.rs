@@ -0,0 +1,555 @@
+// src/ml/stationarity.rs
+use ndarray::{s, Array1, Array2};
+use ndarray_linalg::Inverse;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Deterministic terms included in the test regression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Regression {
+    None,
+    Constant,
+    ConstantTrend,
+}
+
+/// How many lagged differences the ADF regression includes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LagSelection {
+    Fixed(usize),
+    /// Minimise AIC over `0..=max_lag`, with `max_lag` from Schwert's rule
+    Aic,
+    /// Minimise BIC over `0..=max_lag`, with `max_lag` from Schwert's rule
+    Bic,
+}
+
+/// Critical values of a test statistic at the usual significance levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriticalValues {
+    pub one_percent: f64,
+    pub five_percent: f64,
+    pub ten_percent: f64,
+}
+
+/// Outcome of a unit-root or stationarity test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitRootResult {
+    pub statistic: f64,
+    pub p_value: f64,
+    pub lags: usize,  // Lagged differences (ADF) or Newey-West bandwidth (KPSS, PP)
+    pub nobs: usize,  // Observations in the test regression
+    pub critical_values: CriticalValues,
+}
+
+/// MacKinnon (2010) response surface coefficients for the 1%, 5% and 10%
+/// critical values of the Dickey-Fuller t statistic with one variable
+const MACKINNON_CV_NONE: [[f64; 4]; 3] = [
+    [-2.56574, -2.2358, -3.627, 0.0],
+    [-1.94100, -0.2686, -3.365, 31.223],
+    [-1.61682, 0.2656, -2.714, 25.364],
+];
+const MACKINNON_CV_CONSTANT: [[f64; 4]; 3] = [
+    [-3.43035, -6.5393, -16.786, -79.433],
+    [-2.86154, -2.8903, -4.234, -40.040],
+    [-2.56677, -1.5384, -2.809, 0.0],
+];
+const MACKINNON_CV_TREND: [[f64; 4]; 3] = [
+    [-3.95877, -9.0531, -28.428, -134.155],
+    [-3.41049, -4.3904, -9.036, -45.374],
+    [-3.12705, -2.5856, -3.925, -22.380],
+];
+
+/// MacKinnon (1994) p-value approximation for one variable. Below `tau_star`
+/// the p-value is the normal CDF of the `small_p` polynomial, above it of the
+/// `large_p` one; coefficients are already rescaled and in increasing powers.
+struct MacKinnonP {
+    tau_min: f64,
+    tau_star: f64,
+    tau_max: f64,
+    small_p: [f64; 3],
+    large_p: [f64; 4],
+}
+
+const MACKINNON_P_NONE: MacKinnonP = MacKinnonP {
+    tau_min: -19.04,
+    tau_star: -1.04,
+    tau_max: f64::INFINITY,
+    small_p: [0.6344, 1.2378, 0.032496],
+    large_p: [0.4797, 0.93557, -0.06999, 0.033066],
+};
+const MACKINNON_P_CONSTANT: MacKinnonP = MacKinnonP {
+    tau_min: -18.83,
+    tau_star: -1.61,
+    tau_max: 2.74,
+    small_p: [2.1659, 1.4412, 0.038269],
+    large_p: [1.7339, 0.93202, -0.12745, -0.010368],
+};
+const MACKINNON_P_TREND: MacKinnonP = MacKinnonP {
+    tau_min: -16.18,
+    tau_star: -2.89,
+    tau_max: 0.7,
+    small_p: [3.2512, 1.6047, 0.049588],
+    large_p: [2.5261, 0.61654, -0.37956, -0.060285],
+};
+
+/// KPSS (1992) asymptotic critical values at 10%, 5%, 2.5% and 1%
+const KPSS_LEVELS: [f64; 4] = [0.10, 0.05, 0.025, 0.01];
+const KPSS_CV_CONSTANT: [f64; 4] = [0.347, 0.463, 0.574, 0.739];
+const KPSS_CV_TREND: [f64; 4] = [0.119, 0.146, 0.176, 0.216];
+
+/// Augmented Dickey-Fuller test. The null hypothesis is a unit root, so a
+/// small p-value means the series is stationary and needs no (further) differencing.
+///
+/// # Arguments
+///
+/// * `series` - The series to test
+/// * `regression` - Deterministic terms in the test regression
+/// * `lags` - Number of lagged differences, fixed or chosen by information criterion
+///
+/// # Returns
+///
+/// The tau statistic with its MacKinnon p-value and critical values
+pub fn adf(series: &Array1<f64>, regression: Regression, lags: LagSelection) -> Result<UnitRootResult, String> {
+    let diff = difference(series);
+    let n = diff.len();
+
+    let lag = match lags {
+        LagSelection::Fixed(lag) => lag,
+        LagSelection::Aic | LagSelection::Bic => {
+            let usable = n.saturating_sub(deterministic_count(regression) + 3) / 2;
+            let max_lag = schwert_max_lag(series.len()).min(usable);
+            // Every candidate is compared on the sample of the longest lag
+            let mut best = (f64::INFINITY, 0);
+            for lag in 0..=max_lag {
+                let (x, y) = adf_design(series, &diff, regression, lag, max_lag);
+                let fit = ols(&x, &y)?;
+                let nobs = y.len() as f64;
+                let k = x.ncols() as f64;
+                let penalty = if lags == LagSelection::Aic { 2.0 * k } else { k * nobs.ln() };
+                let criterion = nobs * (fit.ssr / nobs).ln() + penalty;
+                if criterion < best.0 {
+                    best = (criterion, lag);
+                }
+            }
+            best.1
+        }
+    };
+
+    if n <= lag + deterministic_count(regression) + 2 {
+        return Err("Not enough observations for the ADF regression".to_string());
+    }
+
+    let (x, y) = adf_design(series, &diff, regression, lag, lag);
+    let fit = ols(&x, &y)?;
+    let nobs = y.len();
+
+    // The lagged level is the first regressor
+    let statistic = fit.beta[0] / fit.std_errors[0];
+
+    Ok(UnitRootResult {
+        statistic,
+        p_value: mackinnon_p_value(statistic, regression)?,
+        lags: lag,
+        nobs,
+        critical_values: mackinnon_critical_values(regression, nobs),
+    })
+}
+
+/// KPSS test. The null hypothesis is (level or trend) stationarity, so a
+/// small p-value means the series needs differencing.
+///
+/// The p-value is interpolated in the KPSS table and therefore clipped to
+/// `[0.01, 0.10]`.
+///
+/// # Arguments
+///
+/// * `series` - The series to test
+/// * `regression` - `Constant` for level or `ConstantTrend` for trend stationarity
+/// * `lags` - Newey-West bandwidth, `None` for `ceil(12 * (n / 100)^(1/4))`
+pub fn kpss(series: &Array1<f64>, regression: Regression, lags: Option<usize>) -> Result<UnitRootResult, String> {
+    let critical = match regression {
+        Regression::Constant => KPSS_CV_CONSTANT,
+        Regression::ConstantTrend => KPSS_CV_TREND,
+        Regression::None => return Err("KPSS test needs a constant or a trend".to_string()),
+    };
+    let n = series.len();
+    if n < 3 {
+        return Err("At least three observations are needed for the KPSS test".to_string());
+    }
+
+    let x = deterministic_terms(regression, n, 0);
+    let residuals = ols(&x, series)?.residuals;
+
+    let mut partial_sum = 0.0;
+    let mut eta = 0.0;
+    for &e in residuals.iter() {
+        partial_sum += e;
+        eta += partial_sum * partial_sum;
+    }
+    eta /= (n * n) as f64;
+
+    let lag = lags.unwrap_or_else(|| default_bandwidth(n)).min(n - 1);
+    let statistic = eta / long_run_variance(&residuals, lag);
+
+    // Linear interpolation in the table, clipped at its ends
+    let p_value = if statistic <= critical[0] {
+        KPSS_LEVELS[0]
+    } else if statistic >= critical[3] {
+        KPSS_LEVELS[3]
+    } else {
+        let i = (0..3).find(|&i| statistic < critical[i + 1]).unwrap_or(2);
+        let weight = (statistic - critical[i]) / (critical[i + 1] - critical[i]);
+        KPSS_LEVELS[i] + weight * (KPSS_LEVELS[i + 1] - KPSS_LEVELS[i])
+    };
+
+    Ok(UnitRootResult {
+        statistic,
+        p_value,
+        lags: lag,
+        nobs: n,
+        critical_values: CriticalValues {
+            one_percent: critical[3],
+            five_percent: critical[1],
+            ten_percent: critical[0],
+        },
+    })
+}
+
+/// Phillips-Perron test (Z-tau form). Same null and critical values as the
+/// ADF test, but serial correlation is handled with a Newey-West correction
+/// instead of lagged differences.
+///
+/// # Arguments
+///
+/// * `series` - The series to test
+/// * `regression` - Deterministic terms in the test regression
+/// * `lags` - Newey-West bandwidth, `None` for `ceil(12 * (n / 100)^(1/4))`
+pub fn phillips_perron(series: &Array1<f64>, regression: Regression, lags: Option<usize>) -> Result<UnitRootResult, String> {
+    let n = series.len();
+    if n <= deterministic_count(regression) + 3 {
+        return Err("Not enough observations for the Phillips-Perron regression".to_string());
+    }
+
+    // y_t on y_{t-1} and the deterministic terms
+    let nobs = n - 1;
+    let deterministic = deterministic_terms(regression, nobs, 1);
+    let mut x = Array2::zeros((nobs, deterministic.ncols() + 1));
+    x.column_mut(0).assign(&series.slice(s![..n - 1]));
+    x.slice_mut(s![.., 1..]).assign(&deterministic);
+    let y = series.slice(s![1..]).to_owned();
+
+    let fit = ols(&x, &y)?;
+    let residuals = &fit.residuals;
+    let t = nobs as f64;
+
+    let lag = lags.unwrap_or_else(|| default_bandwidth(nobs)).min(nobs - 1);
+    let gamma0 = residuals.dot(residuals) / t;
+    let lambda2 = long_run_variance(residuals, lag);
+    let s2 = fit.ssr / (t - x.ncols() as f64);
+    let std_error = fit.std_errors[0];
+    let t_rho = (fit.beta[0] - 1.0) / std_error;
+
+    let statistic = (gamma0 / lambda2).sqrt() * t_rho
+        - 0.5 * (lambda2 - gamma0) / lambda2.sqrt() * t * std_error / s2.sqrt();
+
+    Ok(UnitRootResult {
+        statistic,
+        p_value: mackinnon_p_value(statistic, regression)?,
+        lags: lag,
+        nobs,
+        critical_values: mackinnon_critical_values(regression, nobs),
+    })
+}
+
+/// Finite-sample critical values of the Dickey-Fuller tau statistic
+pub fn mackinnon_critical_values(regression: Regression, nobs: usize) -> CriticalValues {
+    let table = match regression {
+        Regression::None => &MACKINNON_CV_NONE,
+        Regression::Constant => &MACKINNON_CV_CONSTANT,
+        Regression::ConstantTrend => &MACKINNON_CV_TREND,
+    };
+    let t = nobs as f64;
+    let value = |row: &[f64; 4]| row[0] + row[1] / t + row[2] / (t * t) + row[3] / (t * t * t);
+
+    CriticalValues {
+        one_percent: value(&table[0]),
+        five_percent: value(&table[1]),
+        ten_percent: value(&table[2]),
+    }
+}
+
+/// Approximate asymptotic p-value of the Dickey-Fuller tau statistic
+pub fn mackinnon_p_value(statistic: f64, regression: Regression) -> Result<f64, String> {
+    let table = match regression {
+        Regression::None => &MACKINNON_P_NONE,
+        Regression::Constant => &MACKINNON_P_CONSTANT,
+        Regression::ConstantTrend => &MACKINNON_P_TREND,
+    };
+    if statistic > table.tau_max {
+        return Ok(1.0);
+    }
+    if statistic < table.tau_min {
+        return Ok(0.0);
+    }
+
+    let polynomial = if statistic <= table.tau_star {
+        let c = &table.small_p;
+        c[0] + c[1] * statistic + c[2] * statistic * statistic
+    } else {
+        let c = &table.large_p;
+        c[0] + c[1] * statistic + c[2] * statistic.powi(2) + c[3] * statistic.powi(3)
+    };
+
+    let normal = Normal::new(0.0, 1.0).map_err(|e| e.to_string())?;
+    Ok(normal.cdf(polynomial))
+}
+
+/// Schwert's rule for the largest lag worth considering
+fn schwert_max_lag(n: usize) -> usize {
+    (12.0 * (n as f64 / 100.0).powf(0.25)).floor() as usize
+}
+
+/// Default Newey-West bandwidth
+fn default_bandwidth(n: usize) -> usize {
+    (12.0 * (n as f64 / 100.0).powf(0.25)).ceil() as usize
+}
+
+fn deterministic_count(regression: Regression) -> usize {
+    match regression {
+        Regression::None => 0,
+        Regression::Constant => 1,
+        Regression::ConstantTrend => 2,
+    }
+}
+
+/// Constant and trend columns for `rows` observations, the trend starting at `offset + 1`
+fn deterministic_terms(regression: Regression, rows: usize, offset: usize) -> Array2<f64> {
+    let mut x = Array2::zeros((rows, deterministic_count(regression)));
+    for i in 0..rows {
+        if regression != Regression::None {
+            x[[i, 0]] = 1.0;
+        }
+        if regression == Regression::ConstantTrend {
+            x[[i, 1]] = (offset + i + 1) as f64;
+        }
+    }
+    x
+}
+
+fn difference(series: &Array1<f64>) -> Array1<f64> {
+    let n = series.len();
+    if n < 2 {
+        return Array1::zeros(0);
+    }
+    &series.slice(s![1..]) - &series.slice(s![..n - 1])
+}
+
+/// ADF regression of diff[t] on [series[t], diff[t-1..t-lag], deterministic]
+/// for t starting at `start` (at least `lag`)
+fn adf_design(
+    series: &Array1<f64>,
+    diff: &Array1<f64>,
+    regression: Regression,
+    lag: usize,
+    start: usize,
+) -> (Array2<f64>, Array1<f64>) {
+    let rows = diff.len() - start;
+    let deterministic = deterministic_terms(regression, rows, start);
+    let mut x = Array2::zeros((rows, 1 + lag + deterministic.ncols()));
+
+    for i in 0..rows {
+        let t = start + i;
+        x[[i, 0]] = series[t];
+        for j in 1..=lag {
+            x[[i, j]] = diff[t - j];
+        }
+    }
+    x.slice_mut(s![.., 1 + lag..]).assign(&deterministic);
+
+    (x, diff.slice(s![start..]).to_owned())
+}
+
+/// Newey-West long-run variance with a Bartlett kernel
+fn long_run_variance(residuals: &Array1<f64>, lag: usize) -> f64 {
+    let n = residuals.len();
+    let autocovariance = |j: usize| (j..n).map(|t| residuals[t] * residuals[t - j]).sum::<f64>() / n as f64;
+
+    let mut variance = autocovariance(0);
+    for j in 1..=lag {
+        variance += 2.0 * (1.0 - j as f64 / (lag as f64 + 1.0)) * autocovariance(j);
+    }
+    variance
+}
+
+struct OlsFit {
+    beta: Array1<f64>,
+    std_errors: Array1<f64>,
+    residuals: Array1<f64>,
+    ssr: f64,
+}
+
+fn ols(x: &Array2<f64>, y: &Array1<f64>) -> Result<OlsFit, String> {
+    let (n, k) = x.dim();
+    if n <= k {
+        return Err("Not enough observations for the test regression".to_string());
+    }
+
+    let x_t_x_inv = x.t().dot(x).inv().map_err(|_| "Test regression is singular".to_string())?;
+    let beta = x_t_x_inv.dot(&x.t().dot(y));
+    let residuals = y - &x.dot(&beta);
+    let ssr = residuals.dot(&residuals);
+    let s2 = ssr / (n - k) as f64;
+    let std_errors = x_t_x_inv.diag().mapv(|v| (v * s2).sqrt());
+
+    Ok(OlsFit { beta, std_errors, residuals, ssr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::normal_noise;
+
+    fn random_walk(n: usize, seed: u64) -> Array1<f64> {
+        let mut level = 0.0;
+        Array1::from(normal_noise(n, seed).iter().map(|e| { level += e; level }).collect::<Vec<f64>>())
+    }
+
+    fn ar1(n: usize, phi: f64, seed: u64) -> Array1<f64> {
+        let mut value = 0.0;
+        Array1::from(normal_noise(n, seed).iter().map(|e| { value = phi * value + e; value }).collect::<Vec<f64>>())
+    }
+
+    // Fixed room temperature readings for the reference tests. The expected
+    // values were computed in exact rational arithmetic from the definitions
+    // used by statsmodels (adfuller, kpss) and arch (PhillipsPerron), with
+    // the lags fixed so no selection is involved.
+    const READINGS: [f64; 48] = [
+        20.7, 19.7, 20.7, 22.7, 22.8, 23.4, 23.8, 24.0, 24.1, 24.4, 23.7, 22.3, 21.0, 21.4, 21.6, 21.5, 19.9, 19.9,
+        20.1, 19.5, 19.3, 20.9, 21.8, 22.2, 22.2, 21.7, 21.4, 21.8, 21.1, 20.9, 22.0, 21.0, 20.5, 21.7, 21.9, 21.9,
+        21.2, 20.2, 21.1, 19.4, 20.0, 19.8, 19.9, 18.7, 19.6, 20.2, 20.5, 20.9,
+    ];
+
+    #[test]
+    fn test_mackinnon_critical_values_match_published_tables() {
+        // Asymptotic values (MacKinnon 2010, Table 1)
+        let asymptotic = mackinnon_critical_values(Regression::Constant, usize::MAX);
+        assert!((asymptotic.five_percent + 2.86154).abs() < 1e-4);
+        // Fuller's table for T = 100 with a constant: -3.51, -2.89, -2.58
+        let t100 = mackinnon_critical_values(Regression::Constant, 100);
+        assert!((t100.one_percent + 3.50).abs() < 0.015);
+        assert!((t100.five_percent + 2.89).abs() < 0.005);
+        assert!((t100.ten_percent + 2.58).abs() < 0.005);
+        // With a trend, T = 100: -4.04, -3.45, -3.15
+        let trend = mackinnon_critical_values(Regression::ConstantTrend, 100);
+        assert!((trend.five_percent + 3.45).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mackinnon_p_values_at_asymptotic_critical_values() {
+        let cases = [
+            (Regression::None, -2.56574, 0.01),
+            (Regression::None, -1.94100, 0.05),
+            (Regression::Constant, -3.43035, 0.01),
+            (Regression::Constant, -2.86154, 0.05),
+            (Regression::Constant, -2.56677, 0.10),
+            (Regression::ConstantTrend, -3.41049, 0.05),
+        ];
+        for (regression, statistic, expected) in cases {
+            let p = mackinnon_p_value(statistic, regression).unwrap();
+            assert!((p - expected).abs() < 0.003, "{:?}: {} vs {}", regression, p, expected);
+        }
+    }
+
+    #[test]
+    fn test_adf_zero_lags_is_simple_regression_t_statistic() {
+        let series = ar1(60, 0.5, 5);
+        let result = adf(&series, Regression::Constant, LagSelection::Fixed(0)).unwrap();
+
+        // diff[t] = a + g * y[t] by hand
+        let y: Vec<f64> = (0..59).map(|t| series[t + 1] - series[t]).collect();
+        let x: Vec<f64> = (0..59).map(|t| series[t]).collect();
+        let n = 59.0;
+        let mx = x.iter().sum::<f64>() / n;
+        let my = y.iter().sum::<f64>() / n;
+        let sxx: f64 = x.iter().map(|v| (v - mx).powi(2)).sum();
+        let sxy: f64 = x.iter().zip(&y).map(|(a, b)| (a - mx) * (b - my)).sum();
+        let slope = sxy / sxx;
+        let intercept = my - slope * mx;
+        let ssr: f64 = x.iter().zip(&y).map(|(a, b)| (b - intercept - slope * a).powi(2)).sum();
+        let expected = slope / (ssr / (n - 2.0) / sxx).sqrt();
+
+        assert_eq!(result.nobs, 59);
+        assert!((result.statistic - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_adf_matches_reference() {
+        // adfuller(READINGS, maxlag=2, regression="c", autolag=None)
+        let result = adf(&Array1::from(READINGS.to_vec()), Regression::Constant, LagSelection::Fixed(2)).unwrap();
+        assert!((result.statistic + 2.0818635422472).abs() < 1e-9);
+        assert!((result.p_value - 0.2518696165060).abs() < 1e-9);
+        assert_eq!(result.lags, 2);
+        assert_eq!(result.nobs, 45);
+    }
+
+    #[test]
+    fn test_adf_distinguishes_random_walk_and_stationary_series() {
+        let walk = adf(&random_walk(200, 1), Regression::Constant, LagSelection::Aic).unwrap();
+        assert!(walk.p_value > 0.10, "p = {}", walk.p_value);
+
+        let stationary = adf(&ar1(200, 0.5, 2), Regression::Constant, LagSelection::Bic).unwrap();
+        assert!(stationary.p_value < 0.01, "p = {}", stationary.p_value);
+        assert!(stationary.statistic < stationary.critical_values.one_percent);
+    }
+
+    #[test]
+    fn test_kpss_by_hand() {
+        // Residuals -2..2, partial sums -2, -3, -3, -2, 0 and variance 2 without lags
+        let series = Array1::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let result = kpss(&series, Regression::Constant, Some(0)).unwrap();
+        assert!((result.statistic - 26.0 / 50.0).abs() < 1e-12);
+        // Between the 5% (0.463) and 2.5% (0.574) values
+        let expected = 0.05 + (0.52 - 0.463) / (0.574 - 0.463) * (0.025 - 0.05);
+        assert!((result.p_value - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kpss_matches_reference() {
+        // kpss(READINGS, regression="c", nlags=3)
+        let result = kpss(&Array1::from(READINGS.to_vec()), Regression::Constant, Some(3)).unwrap();
+        assert!((result.statistic - 0.4538909350705).abs() < 1e-9);
+        assert!((result.p_value - 0.0539263210903).abs() < 1e-9);
+        assert_eq!(result.lags, 3);
+    }
+
+    #[test]
+    fn test_kpss_distinguishes_random_walk_and_stationary_series() {
+        let walk = kpss(&random_walk(200, 1), Regression::Constant, None).unwrap();
+        assert!(walk.p_value <= 0.05, "p = {}", walk.p_value);
+
+        let stationary = kpss(&ar1(200, 0.3, 4), Regression::Constant, None).unwrap();
+        assert_eq!(stationary.p_value, 0.10);
+    }
+
+    #[test]
+    fn test_phillips_perron_without_bandwidth_equals_adf() {
+        let series = ar1(80, 0.6, 9);
+        let pp = phillips_perron(&series, Regression::ConstantTrend, Some(0)).unwrap();
+        let adf = adf(&series, Regression::ConstantTrend, LagSelection::Fixed(0)).unwrap();
+        assert!((pp.statistic - adf.statistic).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_phillips_perron_matches_reference() {
+        // PhillipsPerron(READINGS, lags=3, trend="c", test_type="tau")
+        let result = phillips_perron(&Array1::from(READINGS.to_vec()), Regression::Constant, Some(3)).unwrap();
+        assert!((result.statistic + 2.2260144332239).abs() < 1e-9);
+        assert!((result.p_value - 0.1969395442999).abs() < 1e-9);
+        assert_eq!(result.lags, 3);
+        assert_eq!(result.nobs, 47);
+    }
+
+    #[test]
+    fn test_phillips_perron_random_walk() {
+        let result = phillips_perron(&random_walk(200, 6), Regression::Constant, None).unwrap();
+        assert!(result.p_value > 0.10, "p = {}", result.p_value);
+    }
+}
@@ -0,0 +1,258 @@
+// src/ml/statistics.rs
+use std::fmt;
+
+use csv::Writer;
+use ndarray::Array2;
+use serde::Serialize;
+use statrs::statistics::{Data, OrderStatistics};
+
+use crate::ML::anomaly::iqr_outliers;
+
+/// Descriptive statistics of one column, NaN readings counted as missing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnSummary {
+    pub name: String,
+    pub count: usize,  // Observed values
+    pub missing: usize,
+    pub mean: f64,
+    pub std: f64,  // Sample standard deviation (n - 1 denominator)
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub skewness: f64,  // Bias-corrected sample skewness, NaN below 3 values
+    pub kurtosis: f64,  // Bias-corrected excess kurtosis, NaN below 4 values
+    pub outliers: usize,  // Values outside the 1.5 IQR Tukey fences, as in `BoxplotData`
+}
+
+/// Summary of every column of a table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub columns: Vec<ColumnSummary>,
+}
+
+/// Describe a single series.
+///
+/// Quartiles follow `statrs` so they agree with `BoxplotData` and
+/// `iqr_outliers`. Statistics that need more values than were observed are NaN.
+///
+/// # Arguments
+///
+/// * `name` - Label of the series in reports
+/// * `values` - The readings, NaN marks a missing value
+///
+/// # Returns
+///
+/// The column summary
+pub fn describe_column(name: &str, values: &[f64]) -> ColumnSummary {
+    let observed: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    let n = observed.len();
+    let mut summary = ColumnSummary {
+        name: name.to_string(),
+        count: n,
+        missing: values.len() - n,
+        mean: f64::NAN,
+        std: f64::NAN,
+        min: f64::NAN,
+        q1: f64::NAN,
+        median: f64::NAN,
+        q3: f64::NAN,
+        max: f64::NAN,
+        skewness: f64::NAN,
+        kurtosis: f64::NAN,
+        outliers: 0,
+    };
+    if n == 0 {
+        return summary;
+    }
+
+    let nf = n as f64;
+    let mean = observed.iter().sum::<f64>() / nf;
+    let moment = |k: i32| observed.iter().map(|v| (v - mean).powi(k)).sum::<f64>() / nf;
+    let (m2, m3, m4) = (moment(2), moment(3), moment(4));
+
+    let mut data = Data::new(observed.clone());
+    summary.mean = mean;
+    summary.min = observed.iter().cloned().fold(f64::INFINITY, f64::min);
+    summary.max = observed.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    summary.q1 = data.lower_quartile();
+    summary.median = data.median();
+    summary.q3 = data.upper_quartile();
+    summary.outliers = iqr_outliers(&observed, 1.5).len();
+    if n > 1 {
+        summary.std = (m2 * nf / (nf - 1.0)).sqrt();
+    }
+    if n > 2 && m2 > 0.0 {
+        let g1 = m3 / m2.powf(1.5);
+        summary.skewness = g1 * (nf * (nf - 1.0)).sqrt() / (nf - 2.0);
+    }
+    if n > 3 && m2 > 0.0 {
+        let g2 = m4 / (m2 * m2) - 3.0;
+        summary.kurtosis = ((nf + 1.0) * g2 + 6.0) * (nf - 1.0) / ((nf - 2.0) * (nf - 3.0));
+    }
+    summary
+}
+
+/// Describe every column of a table, such as one loaded with `read_sensor_table`.
+///
+/// # Arguments
+///
+/// * `data` - Table with one reading per row, NaN marks a missing value
+/// * `names` - Column labels, one per column
+///
+/// # Returns
+///
+/// The summary of every column, in order
+pub fn describe(data: &Array2<f64>, names: &[&str]) -> Result<Summary, String> {
+    if names.len() != data.ncols() {
+        return Err("There must be one name per column".to_string());
+    }
+    let columns = names
+        .iter()
+        .zip(data.columns())
+        .map(|(name, column)| describe_column(name, &column.to_vec()))
+        .collect();
+    Ok(Summary { columns })
+}
+
+const HEADERS: [&str; 13] = [
+    "column", "count", "missing", "mean", "std", "min", "q1", "median", "q3", "max",
+    "skewness", "kurtosis", "outliers",
+];
+
+impl ColumnSummary {
+    fn fields(&self, precision: usize) -> Vec<String> {
+        let number = |v: f64| format!("{:.*}", precision, v);
+        vec![
+            self.name.clone(),
+            self.count.to_string(),
+            self.missing.to_string(),
+            number(self.mean),
+            number(self.std),
+            number(self.min),
+            number(self.q1),
+            number(self.median),
+            number(self.q3),
+            number(self.max),
+            number(self.skewness),
+            number(self.kurtosis),
+            self.outliers.to_string(),
+        ]
+    }
+}
+
+impl Summary {
+    /// Aligned text table with one row per column
+    pub fn to_text_table(&self, precision: usize) -> String {
+        let rows: Vec<Vec<String>> = self.columns.iter().map(|c| c.fields(precision)).collect();
+        let widths: Vec<usize> = (0..HEADERS.len())
+            .map(|i| rows.iter().map(|r| r[i].len()).chain(std::iter::once(HEADERS[i].len())).max().unwrap())
+            .collect();
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(widths.iter())
+                .enumerate()
+                .map(|(i, (cell, &w))| if i == 0 { format!("{:<w$}", cell) } else { format!("{:>w$}", cell) })
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+
+        let mut table = line(HEADERS.to_vec());
+        table.push('\n');
+        table.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+        for row in &rows {
+            table.push('\n');
+            table.push_str(&line(row.iter().map(|s| s.as_str()).collect()));
+        }
+        table
+    }
+
+    /// CSV with a header row and one row per column, full precision
+    pub fn to_csv(&self) -> Result<String, String> {
+        let mut writer = Writer::from_writer(Vec::new());
+        writer.write_record(HEADERS).map_err(|e| e.to_string())?;
+        for column in &self.columns {
+            let mut fields = column.fields(0);
+            for (i, v) in [column.mean, column.std, column.min, column.q1, column.median, column.q3, column.max, column.skewness, column.kurtosis]
+                .iter()
+                .enumerate()
+            {
+                fields[i + 3] = v.to_string();
+            }
+            writer.write_record(&fields).map_err(|e| e.to_string())?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    /// JSON array of column summaries; NaN statistics become `null`
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.columns).map_err(|e| e.to_string())
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_text_table(3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_column() {
+        let values: Vec<f64> = (1..=9).map(|v| v as f64).chain(std::iter::once(f64::NAN)).collect();
+        let summary = describe_column("ramp", &values);
+        assert_eq!(summary.count, 9);
+        assert_eq!(summary.missing, 1);
+        assert_eq!(summary.mean, 5.0);
+        assert!((summary.std - 7.5f64.sqrt()).abs() < 1e-12);
+        assert_eq!((summary.min, summary.median, summary.max), (1.0, 5.0, 9.0));
+        assert!(summary.skewness.abs() < 1e-12);
+        assert!((summary.kurtosis + 1.2).abs() < 1e-12);
+        assert_eq!(summary.outliers, 0);
+
+        // Right-skewed with one far reading
+        let skewed = describe_column("skewed", &[1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 4.0, 5.0, 40.0]);
+        assert!(skewed.skewness > 2.0);
+        assert!(skewed.kurtosis > 3.0);
+        assert_eq!(skewed.outliers, 1);
+
+        let empty = describe_column("empty", &[f64::NAN, f64::NAN]);
+        assert_eq!((empty.count, empty.missing), (0, 2));
+        assert!(empty.mean.is_nan());
+    }
+
+    #[test]
+    fn test_summary_exports() {
+        let mut data = Array2::<f64>::zeros((6, 2));
+        for i in 0..6 {
+            data[[i, 0]] = 20.0 + i as f64;
+            data[[i, 1]] = 60.0 - 2.0 * i as f64;
+        }
+        data[[2, 1]] = f64::NAN;
+        let summary = describe(&data, &["Temperature", "Humidity"]).unwrap();
+        assert_eq!(summary.columns[1].missing, 1);
+        assert!(describe(&data, &["Temperature"]).is_err());
+
+        let text = summary.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("column"));
+        assert!(lines[2].starts_with("Temperature") && lines[2].contains("22.500"));
+
+        let csv = summary.to_csv().unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], HEADERS.join(","));
+        assert!(rows[1].starts_with("Temperature,6,0,22.5,"));
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+        assert_eq!(json[1]["name"], "Humidity");
+        assert_eq!(json[1]["count"], 5);
+        assert_eq!(json[0]["max"], 25.0);
+    }
+}
@@ -0,0 +1,15 @@
+// src/ml/test_utils.rs
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Seeded uniform noise in [-0.5, 0.5), so tests are reproducible
+pub(crate) fn uniform_noise(n: usize, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n).map(|_| rng.gen::<f64>() - 0.5).collect()
+}
+
+/// Seeded noise that is roughly standard normal: the sum of twelve uniforms minus six
+pub(crate) fn normal_noise(n: usize, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n).map(|_| (0..12).map(|_| rng.gen::<f64>()).sum::<f64>() - 6.0).collect()
+}
@@ -0,0 +1,329 @@
+// src/ml/transform.rs
+use ndarray::{s, Array1};
+
+use crate::ML::forecaster::{ForecastInterval, Forecaster};
+use crate::ML::optimize::golden_section;
+
+/// Search range for automatic lambda selection
+const LAMBDA_BOUNDS: (f64, f64) = (-1.0, 2.0);
+
+/// Box-Cox transform of a single value, the log when lambda is 0
+fn transform_value(y: f64, lambda: f64) -> f64 {
+    if lambda.abs() < 1e-8 {
+        y.ln()
+    } else {
+        (y.powf(lambda) - 1.0) / lambda
+    }
+}
+
+/// Inverse Box-Cox of a single value.
+///
+/// Returns NaN when `lambda * z + 1 <= 0`, which has no preimage for negative lambdas.
+fn inverse_value(z: f64, lambda: f64) -> f64 {
+    if lambda.abs() < 1e-8 {
+        z.exp()
+    } else {
+        (lambda * z + 1.0).powf(1.0 / lambda)
+    }
+}
+
+/// Box-Cox transformation.
+///
+/// # Arguments
+///
+/// * `data` - Strictly positive values
+/// * `lambda` - Transformation parameter, 0 gives the natural log
+///
+/// # Returns
+///
+/// The transformed values
+pub fn box_cox(data: &Array1<f64>, lambda: f64) -> Result<Array1<f64>, String> {
+    if data.iter().any(|&y| y <= 0.0) {
+        return Err("Box-Cox transformation requires strictly positive data".to_string());
+    }
+    Ok(data.mapv(|y| transform_value(y, lambda)))
+}
+
+/// Inverse of `box_cox`
+pub fn inv_box_cox(data: &Array1<f64>, lambda: f64) -> Array1<f64> {
+    data.mapv(|z| inverse_value(z, lambda))
+}
+
+/// Choose lambda with Guerrero's method.
+///
+/// Splits the series into consecutive subseries of length `period` and picks
+/// the lambda that makes `sd / mean^(1 - lambda)` most constant across them,
+/// i.e. that minimises its coefficient of variation.
+pub fn guerrero_lambda(data: &Array1<f64>, period: usize) -> Result<f64, String> {
+    if period < 2 {
+        return Err("Guerrero's method needs subseries of at least two values".to_string());
+    }
+    if data.len() < 2 * period {
+        return Err("Guerrero's method needs at least two full subseries".to_string());
+    }
+    if data.iter().any(|&y| y <= 0.0) {
+        return Err("Box-Cox transformation requires strictly positive data".to_string());
+    }
+
+    let blocks: Vec<(f64, f64)> = data
+        .exact_chunks(period)
+        .into_iter()
+        .map(|chunk| {
+            let mean = chunk.sum() / period as f64;
+            let variance = chunk.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / (period - 1) as f64;
+            (mean, variance.sqrt())
+        })
+        .collect();
+
+    let coefficient_of_variation = |lambda: f64| {
+        let ratios: Vec<f64> = blocks.iter().map(|(m, sd)| sd / m.powf(1.0 - lambda)).collect();
+        let k = ratios.len() as f64;
+        let mean = ratios.iter().sum::<f64>() / k;
+        let variance = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (k - 1.0);
+        variance.sqrt() / mean
+    };
+
+    Ok(golden_section(coefficient_of_variation, LAMBDA_BOUNDS.0, LAMBDA_BOUNDS.1, 1e-6))
+}
+
+/// Choose lambda by maximising the profile log-likelihood of a normal model
+/// for the transformed data (Box and Cox, 1964).
+pub fn profile_likelihood_lambda(data: &Array1<f64>) -> Result<f64, String> {
+    if data.len() < 3 {
+        return Err("At least three observations are needed to estimate lambda".to_string());
+    }
+    if data.iter().any(|&y| y <= 0.0) {
+        return Err("Box-Cox transformation requires strictly positive data".to_string());
+    }
+
+    let n = data.len() as f64;
+    let log_sum: f64 = data.iter().map(|y| y.ln()).sum();
+
+    let negative_log_likelihood = |lambda: f64| {
+        let z = data.mapv(|y| transform_value(y, lambda));
+        let mean = z.sum() / n;
+        let variance = z.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        0.5 * n * variance.ln() - (lambda - 1.0) * log_sum
+    };
+
+    Ok(golden_section(negative_log_likelihood, LAMBDA_BOUNDS.0, LAMBDA_BOUNDS.1, 1e-6))
+}
+
+/// How `BoxCox` picks its lambda when fitting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LambdaMethod {
+    /// Use the given lambda, 0.0 is a log transform
+    Fixed(f64),
+    /// Guerrero's method with subseries of `period` samples (one seasonal cycle)
+    Guerrero { period: usize },
+    /// Maximise the Box-Cox profile likelihood
+    ProfileLikelihood,
+}
+
+/// Fits any forecaster on Box-Cox transformed data and maps its output back.
+/// The model can also be a `Box<dyn Forecaster>` from `ModelConfig::build`.
+///
+/// Prediction intervals are back-transformed bound by bound. Point forecasts
+/// are bias adjusted by default so they estimate the mean rather than the
+/// median on the original scale, which uses the wrapped model's forecast variance.
+pub struct BoxCox<F: Forecaster> {
+    pub method: LambdaMethod,
+    pub bias_adjust: bool,
+    model: F,
+    lambda: Option<f64>,
+    history: Option<Array1<f64>>,  // Untransformed training data
+}
+
+impl<F: Forecaster> BoxCox<F> {
+    /// Wrap a model, choosing lambda with `method` at every fit
+    pub fn new(model: F, method: LambdaMethod) -> Self {
+        BoxCox {
+            method,
+            bias_adjust: true,
+            model,
+            lambda: None,
+            history: None,
+        }
+    }
+
+    /// Wrap a model in a log transform
+    pub fn log(model: F) -> Self {
+        BoxCox::new(model, LambdaMethod::Fixed(0.0))
+    }
+
+    /// Lambda used by the last fit
+    pub fn lambda(&self) -> Option<f64> {
+        self.lambda
+    }
+
+    /// The wrapped model, which works on the transformed scale
+    pub fn inner(&self) -> &F {
+        &self.model
+    }
+
+    fn fitted_lambda(&self) -> Result<f64, String> {
+        self.lambda.ok_or_else(|| "Model must be fitted before forecasting".to_string())
+    }
+
+    /// Back-transformed mean of a normal forecast with transformed-scale variance `variance`
+    fn bias_adjusted(z: f64, variance: f64, lambda: f64) -> f64 {
+        let base = lambda * z + 1.0;
+        inverse_value(z, lambda) * (1.0 + variance * (1.0 - lambda) / (2.0 * base * base))
+    }
+}
+
+impl<F: Forecaster> Forecaster for BoxCox<F> {
+    fn fit(&mut self, data: &Array1<f64>) -> Result<(), String> {
+        let lambda = match self.method {
+            LambdaMethod::Fixed(lambda) => lambda,
+            LambdaMethod::Guerrero { period } => guerrero_lambda(data, period)?,
+            LambdaMethod::ProfileLikelihood => profile_likelihood_lambda(data)?,
+        };
+
+        self.model.fit(&box_cox(data, lambda)?)?;
+        self.lambda = Some(lambda);
+        self.history = Some(data.clone());
+        Ok(())
+    }
+
+    fn predict(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        let lambda = self.fitted_lambda()?;
+        let transformed = self.model.predict(horizon)?;
+
+        if !self.bias_adjust {
+            return Ok(inv_box_cox(&transformed, lambda));
+        }
+
+        let variance = self.model.forecast_variance(horizon)?;
+        Ok(Array1::from_shape_fn(horizon, |h| {
+            Self::bias_adjusted(transformed[h], variance[h], lambda)
+        }))
+    }
+
+    /// Back-transformed fitted values, i.e. medians on the original scale
+    fn fitted_values(&self) -> Result<Array1<f64>, String> {
+        let lambda = self.fitted_lambda()?;
+        Ok(inv_box_cox(&self.model.fitted_values()?, lambda))
+    }
+
+    /// Residuals on the original scale
+    fn residuals(&self) -> Result<Array1<f64>, String> {
+        let fitted = self.fitted_values()?;
+        match &self.history {
+            Some(history) => Ok(&history.slice(s![history.len() - fitted.len()..]) - &fitted),
+            None => Err("Model must be fitted before computing residuals".to_string()),
+        }
+    }
+
+    /// Delta-method approximation of the variance on the original scale
+    fn forecast_variance(&self, horizon: usize) -> Result<Array1<f64>, String> {
+        let lambda = self.fitted_lambda()?;
+        let transformed = self.model.predict(horizon)?;
+        let variance = self.model.forecast_variance(horizon)?;
+        Ok(Array1::from_shape_fn(horizon, |h| {
+            let derivative = inverse_value(transformed[h], lambda).powf(1.0 - lambda);
+            variance[h] * derivative * derivative
+        }))
+    }
+
+    /// Bounds are quantiles, so they are back-transformed directly and stay
+    /// asymmetric around the (bias adjusted) point forecast.
+    fn predict_interval(&self, horizon: usize, level: f64) -> Result<ForecastInterval, String> {
+        let lambda = self.fitted_lambda()?;
+        let interval = self.model.predict_interval(horizon, level)?;
+        Ok(ForecastInterval {
+            mean: self.predict(horizon)?,
+            lower: inv_box_cox(&interval.lower, lambda),
+            upper: inv_box_cox(&interval.upper, lambda),
+            level,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::arima::ARIMA;
+    use crate::ML::forecaster::ModelConfig;
+
+    #[test]
+    fn test_box_cox_roundtrip() {
+        let data = Array1::from(vec![0.5, 1.0, 4.0, 9.0]);
+        let transformed = box_cox(&data, 0.5).unwrap();
+        // (sqrt(y) - 1) / 0.5
+        assert!((transformed[3] - 4.0).abs() < 1e-12);
+        assert!((transformed[1]).abs() < 1e-12);
+
+        for &lambda in &[-0.5, 0.0, 0.5, 1.5] {
+            let restored = inv_box_cox(&box_cox(&data, lambda).unwrap(), lambda);
+            for (a, b) in restored.iter().zip(data.iter()) {
+                assert!((a - b).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_box_cox_rejects_non_positive() {
+        assert!(box_cox(&Array1::from(vec![1.0, 0.0]), 0.5).is_err());
+    }
+
+    #[test]
+    fn test_guerrero_detects_multiplicative_growth() {
+        // Seasonal swings proportional to the level: a log transform stabilises them
+        let season = [1.1, 1.3, 0.9, 0.7];
+        let data = Array1::from(
+            (0..48).map(|t| 10.0 * 1.05f64.powi(t as i32) * season[t % 4]).collect::<Vec<f64>>(),
+        );
+        let lambda = guerrero_lambda(&data, 4).unwrap();
+        assert!(lambda.abs() < 0.05, "lambda = {}", lambda);
+    }
+
+    #[test]
+    fn test_profile_likelihood_of_lognormal_like_data() {
+        // exp of evenly spread values is symmetric on the log scale
+        let data = Array1::from((0..41).map(|i| (-2.0 + 0.1 * i as f64).exp()).collect::<Vec<f64>>());
+        let lambda = profile_likelihood_lambda(&data).unwrap();
+        assert!(lambda.abs() < 1e-3, "lambda = {}", lambda);
+    }
+
+    #[test]
+    fn test_log_bias_adjustment() {
+        let data = Array1::from(
+            vec![20.0, 22.0, 21.0, 25.0, 24.0, 27.0, 26.0, 30.0, 29.0, 33.0, 31.0, 36.0],
+        );
+        let mut model = BoxCox::log(ARIMA::new(0, 1, 0));
+        model.fit(&data).unwrap();
+
+        let z = model.inner().predict(2).unwrap();
+        let variance = model.inner().forecast_variance(2).unwrap();
+        let forecasts = model.predict(2).unwrap();
+        for h in 0..2 {
+            // Mean of a lognormal: exp(mu) * (1 + sigma2 / 2) to second order
+            assert!((forecasts[h] - z[h].exp() * (1.0 + variance[h] / 2.0)).abs() < 1e-10);
+        }
+
+        let interval = model.predict_interval(2, 0.95).unwrap();
+        for h in 0..2 {
+            assert!(interval.lower[h] < z[h].exp() && z[h].exp() < interval.upper[h]);
+            // Back-transformed bounds are skewed upwards
+            assert!(interval.upper[h] - z[h].exp() > z[h].exp() - interval.lower[h]);
+        }
+    }
+
+    #[test]
+    fn test_wraps_configured_model() {
+        let data = Array1::from(
+            vec![20.0, 22.0, 21.0, 25.0, 24.0, 27.0, 26.0, 30.0, 29.0, 33.0, 31.0, 36.0],
+        );
+        let mut boxed = BoxCox::log(ModelConfig::Arima { p: 0, d: 1, q: 0 }.build());
+        boxed.fit(&data).unwrap();
+        let mut concrete = BoxCox::log(ARIMA::new(0, 1, 0));
+        concrete.fit(&data).unwrap();
+
+        assert_eq!(boxed.predict(3).unwrap(), concrete.predict(3).unwrap());
+        assert_eq!(
+            boxed.predict_interval(3, 0.9).unwrap(),
+            concrete.predict_interval(3, 0.9).unwrap()
+        );
+    }
+}
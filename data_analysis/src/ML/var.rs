@@ -0,0 +1,390 @@
+// src/ml/var.rs
+use ndarray::{s, Array1, Array2, Axis};
+use ndarray_linalg::{Cholesky, Determinant, Inverse, UPLO};
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+/// Criterion used to choose the lag order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InformationCriterion {
+    Aic,
+    Bic,
+}
+
+/// F test of whether one channel's lags help predict another
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrangerTest {
+    pub statistic: f64,
+    pub p_value: f64,
+    pub df_num: f64,
+    pub df_den: f64,
+}
+
+/// Vector autoregression of order p with an intercept:
+/// `y_t = c + A_1 y_{t-1} + ... + A_p y_{t-p} + e_t`, `e_t ~ N(0, Sigma)`.
+///
+/// Data is an `Array2` with one row per time step and one column per channel.
+/// Every equation is fitted by OLS on the same regressors.
+pub struct VAR {
+    p: usize,  // Lag order
+    intercept: Option<Array1<f64>>,
+    coefficients: Option<Vec<Array2<f64>>>,  // A_1..A_p, row i holds equation i
+    sigma: Option<Array2<f64>>,  // Residual covariance, degrees of freedom corrected
+    history: Option<Array2<f64>>,
+    residuals: Option<Array2<f64>>,
+}
+
+/// Intercept, lag matrices, residual covariance and training data of a fitted model
+type FittedParts<'a> = (&'a Array1<f64>, &'a [Array2<f64>], &'a Array2<f64>, &'a Array2<f64>);
+
+/// Rows `start..n` of the data and their lagged regressors `[1, y_{t-1}, ..., y_{t-p}]`
+fn design(data: &Array2<f64>, p: usize, start: usize) -> (Array2<f64>, Array2<f64>) {
+    let (n, k) = data.dim();
+    let y = data.slice(s![start.., ..]).to_owned();
+    let mut x = Array2::ones((n - start, 1 + k * p));
+    for (row, t) in (start..n).enumerate() {
+        for lag in 1..=p {
+            x.slice_mut(s![row, 1 + (lag - 1) * k..1 + lag * k]).assign(&data.row(t - lag));
+        }
+    }
+    (y, x)
+}
+
+/// OLS coefficients (one column per equation) and residuals
+fn least_squares(x: &Array2<f64>, y: &Array2<f64>) -> Result<(Array2<f64>, Array2<f64>), String> {
+    let x_t_x_inv = x.t().dot(x).inv().map_err(|_| "VAR regression is singular".to_string())?;
+    let beta = x_t_x_inv.dot(&x.t().dot(y));
+    let residuals = y - &x.dot(&beta);
+    Ok((beta, residuals))
+}
+
+impl VAR {
+    /// Create a new VAR model of order `p`
+    pub fn new(p: usize) -> Self {
+        VAR {
+            p,
+            intercept: None,
+            coefficients: None,
+            sigma: None,
+            history: None,
+            residuals: None,
+        }
+    }
+
+    /// Lag order p
+    pub fn order(&self) -> usize {
+        self.p
+    }
+
+    /// Intercept c of every equation, once fitted
+    pub fn intercept(&self) -> Option<&Array1<f64>> {
+        self.intercept.as_ref()
+    }
+
+    /// Lag matrices A_1..A_p, row i holding equation i, once fitted
+    pub fn coefficients(&self) -> Option<&[Array2<f64>]> {
+        self.coefficients.as_deref()
+    }
+
+    /// Residual covariance Sigma, degrees of freedom corrected, once fitted
+    pub fn sigma(&self) -> Option<&Array2<f64>> {
+        self.sigma.as_ref()
+    }
+
+    /// Check if the model has been fitted
+    pub fn is_fitted(&self) -> bool {
+        self.intercept.is_some() && self.coefficients.is_some() && self.sigma.is_some()
+    }
+
+    fn validate(data: &Array2<f64>, p: usize) -> Result<(), String> {
+        let (n, k) = data.dim();
+        if k == 0 {
+            return Err("Data must have at least one channel".to_string());
+        }
+        if p == 0 {
+            return Err("VAR order must be at least 1".to_string());
+        }
+        if n <= p + 1 + k * p {
+            return Err("Not enough observations for the VAR order and number of channels".to_string());
+        }
+        if data.iter().any(|v| !v.is_finite()) {
+            return Err("VAR requires finite values, impute gaps first".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fit the model by equation-wise OLS.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - One row per time step, one column per channel
+    pub fn fit(&mut self, data: &Array2<f64>) -> Result<(), String> {
+        Self::validate(data, self.p)?;
+        let k = data.ncols();
+        let (y, x) = design(data, self.p, self.p);
+        let (beta, residuals) = least_squares(&x, &y)?;
+
+        let dof = (y.nrows() - x.ncols()) as f64;
+        self.sigma = Some(residuals.t().dot(&residuals) / dof);
+        self.intercept = Some(beta.row(0).to_owned());
+        self.coefficients = Some(
+            (0..self.p)
+                .map(|lag| beta.slice(s![1 + lag * k..1 + (lag + 1) * k, ..]).t().to_owned())
+                .collect(),
+        );
+        self.history = Some(data.clone());
+        self.residuals = Some(residuals);
+        Ok(())
+    }
+
+    /// Choose the order in `1..=max_p` minimising an information criterion.
+    ///
+    /// Every candidate is fitted on the same observations (those after the
+    /// first `max_p`) so the criteria are comparable.
+    pub fn select_order(data: &Array2<f64>, max_p: usize, criterion: InformationCriterion) -> Result<usize, String> {
+        Self::validate(data, max_p)?;
+        let k = data.ncols() as f64;
+
+        let mut best = (0, f64::INFINITY);
+        for p in 1..=max_p {
+            let (y, x) = design(data, p, max_p);
+            let (_, residuals) = least_squares(&x, &y)?;
+            let t = y.nrows() as f64;
+            let sigma_ml = residuals.t().dot(&residuals) / t;
+            let det = sigma_ml.det().map_err(|_| "Residual covariance is singular".to_string())?;
+
+            let parameters = k * k * p as f64;
+            let penalty = match criterion {
+                InformationCriterion::Aic => 2.0 * parameters / t,
+                InformationCriterion::Bic => parameters * t.ln() / t,
+            };
+            let value = det.ln() + penalty;
+            if value < best.1 {
+                best = (p, value);
+            }
+        }
+        Ok(best.0)
+    }
+
+    fn fitted_parts(&self) -> Result<FittedParts<'_>, String> {
+        match (&self.intercept, &self.coefficients, &self.sigma, &self.history) {
+            (Some(c), Some(a), Some(sigma), Some(history)) => Ok((c, a, sigma, history)),
+            _ => Err("Model must be fitted first".to_string()),
+        }
+    }
+
+    /// Residuals of every equation, one row per time step after the first `p`
+    pub fn residuals(&self) -> Result<Array2<f64>, String> {
+        self.residuals
+            .clone()
+            .ok_or_else(|| "Model must be fitted before computing residuals".to_string())
+    }
+
+    /// Forecast `steps` rows past the end of the training data
+    pub fn forecast(&self, steps: usize) -> Result<Array2<f64>, String> {
+        let (intercept, coefficients, _, history) = self.fitted_parts()?;
+        let n = history.nrows();
+
+        // Most recent rows first, extended with the forecasts as they are made
+        let mut recent: Vec<Array1<f64>> = (0..self.p).map(|lag| history.row(n - 1 - lag).to_owned()).collect();
+        let mut forecasts = Array2::zeros((steps, intercept.len()));
+        for h in 0..steps {
+            let mut next = intercept.clone();
+            for (a, y) in coefficients.iter().zip(&recent) {
+                next = next + a.dot(y);
+            }
+            forecasts.row_mut(h).assign(&next);
+            recent.insert(0, next);
+            recent.truncate(self.p);
+        }
+        Ok(forecasts)
+    }
+
+    /// Moving-average weights `Phi_0 = I, Phi_i = sum_j Phi_{i-j} A_j` for `0..periods`
+    fn ma_weights(&self, periods: usize) -> Result<Vec<Array2<f64>>, String> {
+        let (intercept, coefficients, _, _) = self.fitted_parts()?;
+        let k = intercept.len();
+        let mut phi: Vec<Array2<f64>> = vec![Array2::eye(k)];
+        for i in 1..periods {
+            let mut next = Array2::zeros((k, k));
+            for j in 1..=i.min(self.p) {
+                next = next + phi[i - j].dot(&coefficients[j - 1]);
+            }
+            phi.push(next);
+        }
+        phi.truncate(periods);
+        Ok(phi)
+    }
+
+    /// Covariance matrices of the 1..=`steps` step forecast errors
+    pub fn forecast_covariance(&self, steps: usize) -> Result<Vec<Array2<f64>>, String> {
+        let (_, _, sigma, _) = self.fitted_parts()?;
+        let mut total = Array2::zeros(sigma.dim());
+        Ok(self
+            .ma_weights(steps)?
+            .iter()
+            .map(|phi| {
+                total = &total + &phi.dot(sigma).dot(&phi.t());
+                total.clone()
+            })
+            .collect())
+    }
+
+    /// Impulse responses for horizons `0..periods`.
+    ///
+    /// Entry `[i, j]` of the matrix at horizon h is the response of channel i
+    /// to a unit shock in channel j h steps earlier. Orthogonalised responses
+    /// use one-standard-deviation shocks from the Cholesky factor of Sigma, so
+    /// they depend on the column order of the data.
+    pub fn impulse_response(&self, periods: usize, orthogonalized: bool) -> Result<Vec<Array2<f64>>, String> {
+        let (_, _, sigma, _) = self.fitted_parts()?;
+        let phi = self.ma_weights(periods)?;
+        if !orthogonalized {
+            return Ok(phi);
+        }
+        let factor = sigma
+            .cholesky(UPLO::Lower)
+            .map_err(|_| "Residual covariance is not positive definite".to_string())?;
+        Ok(phi.iter().map(|p| p.dot(&factor)).collect())
+    }
+
+    /// Test whether the lags of channel `cause` Granger-cause channel `effect`.
+    ///
+    /// Compares the fitted equation of `effect` with one refitted without the
+    /// lags of `cause`. A small p-value means those lags improve the prediction.
+    pub fn granger_causality(&self, cause: usize, effect: usize) -> Result<GrangerTest, String> {
+        let (intercept, _, _, history) = self.fitted_parts()?;
+        let k = intercept.len();
+        if cause >= k || effect >= k || cause == effect {
+            return Err("Cause and effect must be two different channels".to_string());
+        }
+
+        let (y, x) = design(history, self.p, self.p);
+        let y = y.column(effect).to_owned().insert_axis(Axis(1));
+        let kept: Vec<usize> = (0..x.ncols())
+            .filter(|&col| col == 0 || (col - 1) % k != cause)
+            .collect();
+        let x_restricted = x.select(Axis(1), &kept);
+
+        let ssr = |residuals: &Array2<f64>| residuals.iter().map(|r| r * r).sum::<f64>();
+        let ssr_full = ssr(&least_squares(&x, &y)?.1);
+        let ssr_restricted = ssr(&least_squares(&x_restricted, &y)?.1);
+
+        let df_num = self.p as f64;
+        let df_den = (y.nrows() - x.ncols()) as f64;
+        let statistic = ((ssr_restricted - ssr_full) / df_num) / (ssr_full / df_den);
+        let distribution = FisherSnedecor::new(df_num, df_den).map_err(|e| e.to_string())?;
+
+        Ok(GrangerTest {
+            statistic,
+            p_value: distribution.sf(statistic),
+            df_num,
+            df_den,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ML::test_utils::normal_noise;
+
+    /// Humidity (column 1) drives temperature (column 0), not the other way round
+    fn simulate(n: usize, second_lag: bool) -> Array2<f64> {
+        let e0 = normal_noise(n, 1);
+        let e1 = normal_noise(n, 2);
+        let mut data = Array2::zeros((n, 2));
+        for t in 2..n {
+            let mut temperature = 1.0 + 0.5 * data[[t - 1, 0]] + 0.3 * data[[t - 1, 1]] + e0[t];
+            let humidity = 2.0 + 0.4 * data[[t - 1, 1]] + e1[t];
+            if second_lag {
+                temperature -= 0.3 * data[[t - 2, 0]];
+            }
+            data[[t, 0]] = temperature;
+            data[[t, 1]] = humidity;
+        }
+        data
+    }
+
+    #[test]
+    fn test_fit_recovers_coefficients() {
+        let data = simulate(2000, false);
+        let mut model = VAR::new(1);
+        model.fit(&data).unwrap();
+
+        assert!(model.is_fitted());
+        assert_eq!(model.order(), 1);
+        let a = &model.coefficients().unwrap()[0];
+        let expected = [[0.5, 0.3], [0.0, 0.4]];
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((a[[i, j]] - expected[i][j]).abs() < 0.05, "A[{}, {}] = {}", i, j, a[[i, j]]);
+            }
+        }
+        let sigma = model.sigma().unwrap();
+        assert!((sigma[[0, 0]] - 1.0).abs() < 0.1);
+        assert!(sigma[[0, 1]].abs() < 0.1);
+    }
+
+    #[test]
+    fn test_granger_causality_direction() {
+        let data = simulate(500, false);
+        let mut model = VAR::new(1);
+        model.fit(&data).unwrap();
+
+        assert!(model.granger_causality(1, 0).unwrap().p_value < 1e-6);
+        assert!(model.granger_causality(0, 1).unwrap().p_value > 0.01);
+        assert!(model.granger_causality(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_select_order() {
+        let data = simulate(1000, true);
+        assert_eq!(VAR::select_order(&data, 5, InformationCriterion::Bic).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_forecast_and_impulse_response() {
+        let data = simulate(300, false);
+        let mut model = VAR::new(1);
+        model.fit(&data).unwrap();
+
+        let c = model.intercept().unwrap().clone();
+        let a = model.coefficients().unwrap()[0].clone();
+        let last = data.row(299).to_owned();
+        let forecasts = model.forecast(2).unwrap();
+        let one_step = &c + &a.dot(&last);
+        let two_step = &c + &a.dot(&one_step);
+        for j in 0..2 {
+            assert!((forecasts[[0, j]] - one_step[j]).abs() < 1e-10);
+            assert!((forecasts[[1, j]] - two_step[j]).abs() < 1e-10);
+        }
+
+        let responses = model.impulse_response(3, false).unwrap();
+        assert_eq!(responses[0], Array2::<f64>::eye(2));
+        let a_squared = a.dot(&a);
+        for (x, y) in responses[2].iter().zip(a_squared.iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+
+        // Orthogonalised shocks at horizon 0 reproduce Sigma
+        let sigma = model.sigma().unwrap().clone();
+        let shock = &model.impulse_response(1, true).unwrap()[0];
+        for (x, y) in shock.dot(&shock.t()).iter().zip(sigma.iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+
+        let covariance = model.forecast_covariance(2).unwrap();
+        assert_eq!(covariance[0], sigma);
+        let expected = &sigma + &a.dot(&sigma).dot(&a.t());
+        for (x, y) in covariance[1].iter().zip(expected.iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_unfitted_and_short_data() {
+        assert!(VAR::new(1).forecast(1).is_err());
+        assert!(!VAR::new(1).is_fitted());
+        assert!(VAR::new(2).fit(&Array2::zeros((5, 2))).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use csv::Writer;
+use csv::{Reader, Writer};
+use ndarray::Array2;
 use std::fs::File;
 use std::io::{self, BufRead,BufReader};
 use serialport::{ClearBuffer, SerialPort};
@@ -36,4 +37,73 @@ pub fn write_sensor_data(port:impl SerialPort, file_path: &str)-> io::Result<()>
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+
+/// Reads numeric columns of a sensor CSV file into a matrix, one row per record.
+///
+/// Empty or unparseable cells become NaN, so gaps can be imputed afterwards.
+pub fn read_sensor_table(file_path: &str, columns: &[&str]) -> io::Result<Array2<f64>> {
+    let mut reader = Reader::from_path(file_path)?;
+    let headers = reader.headers()?.clone();
+
+    let mut indices = Vec::with_capacity(columns.len());
+    for column in columns {
+        match headers.iter().position(|h| h.trim() == *column) {
+            Some(index) => indices.push(index),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Column {} not found", column),
+                ))
+            }
+        }
+    }
+
+    let mut values = Vec::new();
+    let mut rows = 0;
+    for record in reader.records() {
+        let record = record?;
+        for &index in &indices {
+            let value = record.get(index).and_then(|cell| cell.trim().parse::<f64>().ok());
+            values.push(value.unwrap_or(f64::NAN));
+        }
+        rows += 1;
+    }
+
+    Array2::from_shape_vec((rows, columns.len()), values)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_read_sensor_table() {
+        let path = std::env::temp_dir().join(format!("read_sensor_table_{}.csv", std::process::id()));
+        fs::write(
+            &path,
+            "Timestamp,Temperature,Humidity\n\
+             2024-01-01 00:00,21.5,40\n\
+             2024-01-01 00:01,,41.5\n\
+             2024-01-01 00:02,err, 42 \n",
+        )
+        .unwrap();
+        let file_path = path.to_str().unwrap();
+
+        // Columns come out in the requested order, gaps and garbage as NaN
+        let table = read_sensor_table(file_path, &["Humidity", "Temperature"]).unwrap();
+        assert_eq!(table.dim(), (3, 2));
+        assert_eq!(table.column(0).to_vec(), vec![40.0, 41.5, 42.0]);
+        assert_eq!(table[[0, 1]], 21.5);
+        assert!(table[[1, 1]].is_nan());
+        assert!(table[[2, 1]].is_nan());
+
+        let missing = read_sensor_table(file_path, &["Temperature", "Pressure"]).unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::InvalidInput);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,8 +3,9 @@ pub mod utils;
 pub mod data;
 pub mod Graphics;
 pub mod Misc;
+pub mod ML;
 
 // re exporta items mas usados
 pub use arduino::connection::recibir_senales_arduino;
 pub use arduino::port::buscar_puerto_arduino;
-pub use data::csv_handler::write_sensor_data;
\ No newline at end of file
+pub use data::csv_handler::{write_sensor_data, read_sensor_table};
\ No newline at end of file
@@ -1,16 +1,16 @@
-mod ML;
 mod Misc;
-use core::f64;
 
-use ML::satisfaccion::calcular_satisfaccion;
+use data_analysis::ML::satisfaccion::calcular_satisfaccion;
+use data_analysis::ML::statistics::{describe_column, Summary};
 use Misc::random_num::rand_list_f64;
 
 fn main() {
 
 
     let lista = rand_list_f64(100, 10.0, 40.0);
-    let min = lista.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max:f64 = lista.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let resumen = Summary { columns: vec![describe_column("lista", &lista)] };
+    println!("{}", resumen);
+    let (min, max) = (resumen.columns[0].min, resumen.columns[0].max);
     let vos:Vec<f64> = vec![24.0,40.0,90.0];
 
 